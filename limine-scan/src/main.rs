@@ -0,0 +1,97 @@
+//! List the Limine requests contained in a compiled executable.
+//!
+//! Usage: `limine-scan <executable>`
+//!
+//! Exits with a non-zero status if the executable cannot be read, or if any
+//! request or base revision tag lies outside of the request markers.
+
+use std::{env, fs, process::ExitCode};
+
+use limine::scan::{Location, Scan};
+
+fn describe(location: &Location<'_>) -> String {
+    format!(
+        "{}+{:#x} (file offset {:#x}, address {:#x})",
+        location.section, location.section_offset, location.file_offset, location.address,
+    )
+}
+
+fn markers(in_markers: Option<bool>) -> &'static str {
+    match in_markers {
+        Some(true) => "inside markers",
+        Some(false) => "OUTSIDE MARKERS",
+        None => "markers missing",
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let (Some(path), None) = (args.next(), args.next()) else {
+        eprintln!("usage: limine-scan <executable>");
+        return ExitCode::FAILURE;
+    };
+
+    let image = match fs::read(&path) {
+        Ok(image) => image,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let scan = match Scan::new(&image) {
+        Ok(scan) => scan,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut ok = true;
+
+    match scan.start_marker() {
+        Some(location) => println!("start marker: {}", describe(&location)),
+        None => println!("start marker: not found"),
+    }
+    match scan.end_marker() {
+        Some(location) => println!("end marker: {}", describe(&location)),
+        None => println!("end marker: not found"),
+    }
+
+    let mut base_revisions = 0;
+    for tag in scan.base_revisions() {
+        base_revisions += 1;
+        ok &= tag.in_markers != Some(false);
+        println!(
+            "base revision {}: {}, {}",
+            tag.revision,
+            describe(&tag.location),
+            markers(tag.in_markers)
+        );
+    }
+    if base_revisions == 0 {
+        println!("base revision: not found");
+    }
+
+    for request in scan.requests() {
+        ok &= request.in_markers != Some(false);
+        let name = match request.name {
+            Some(name) => name.to_string(),
+            None => format!(
+                "unknown request {:#018x}:{:#018x}",
+                request.id[2], request.id[3]
+            ),
+        };
+        println!(
+            "{name} (revision {}): {}, {}",
+            request.revision,
+            describe(&request.location),
+            markers(request.in_markers)
+        );
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
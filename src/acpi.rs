@@ -0,0 +1,804 @@
+//! Discovery of the ACPI tables, starting from the [RSDP
+//! response](crate::response::RsdpResponse).
+//!
+//! Tables are read in place through the higher-half direct map, and every
+//! table's signature, length and checksum is validated before it is returned.
+//! Typed views are provided for the [MADT](Madt), [FADT](Fadt), [HPET](Hpet),
+//! [MCFG](Mcfg) and [SRAT](Srat); other tables can be read from
+//! [`Sdt::data`].
+//!
+//! # Examples
+//! ```rust
+//! use limine::{
+//!     acpi::{Acpi, MadtEntry},
+//!     addr::PhysAddr,
+//! };
+//! # // Lay out an RSDP, an XSDT and a MADT in fake physical memory at 0x10000.
+//! # fn table(memory: &mut [u8], offset: usize, signature: &[u8; 4], body: &[u8]) {
+//! #     let length = 36 + body.len();
+//! #     let table = &mut memory[offset..offset + length];
+//! #     table[..4].copy_from_slice(signature);
+//! #     table[4..8].copy_from_slice(&(length as u32).to_le_bytes());
+//! #     table[36..].copy_from_slice(body);
+//! #     table[9] = 0u8.wrapping_sub(table.iter().fold(0u8, |a, &b| a.wrapping_add(b)));
+//! # }
+//! # let mut words = vec![0u64; 0x100];
+//! # let memory = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), 0x800) };
+//! # memory[..8].copy_from_slice(b"RSD PTR ");
+//! # memory[15] = 2;
+//! # memory[20..24].copy_from_slice(&36u32.to_le_bytes());
+//! # memory[24..32].copy_from_slice(&0x10040u64.to_le_bytes());
+//! # memory[8] = 0u8.wrapping_sub(memory[..20].iter().fold(0u8, |a, &b| a.wrapping_add(b)));
+//! # memory[32] = 0u8.wrapping_sub(memory[..36].iter().fold(0u8, |a, &b| a.wrapping_add(b)));
+//! # table(memory, 0x40, b"XSDT", &0x10080u64.to_le_bytes());
+//! # table(memory, 0x80, b"APIC", &[
+//! #     0x00, 0x00, 0xe0, 0xfe, 1, 0, 0, 0, // local APIC address, flags
+//! #     0, 8, 0, 0, 1, 0, 0, 0, // processor 0, APIC ID 0, enabled
+//! #     1, 12, 2, 0, 0x00, 0x00, 0xc0, 0xfe, 0, 0, 0, 0, // I/O APIC 2
+//! # ]);
+//! # let hhdm_offset = (memory.as_ptr() as u64).wrapping_sub(0x10000);
+//! # let rsdp_address = PhysAddr::new(0x10000);
+//!
+//! // SAFETY: the tables are mapped in the higher-half direct map
+//! let acpi = unsafe { Acpi::new(rsdp_address, hhdm_offset) }.unwrap();
+//! assert_eq!(acpi.tables().count(), 1);
+//!
+//! let madt = acpi.madt().unwrap();
+//! assert_eq!(madt.local_apic_address(), 0xfee0_0000);
+//! for entry in madt.entries() {
+//!     match entry {
+//!         MadtEntry::LocalApic { apic_id, flags, .. } => assert!(apic_id == 0 && flags & 1 != 0),
+//!         MadtEntry::IoApic { address, .. } => assert_eq!(address, 0xfec0_0000),
+//!         _ => unreachable!(),
+//!     }
+//! }
+//! ```
+
+use core::{marker::PhantomData, mem::size_of};
+
+use crate::{
+    addr::PhysAddr,
+    phys::{checksum, map, read, read_u16, read_u32, read_u64, read_u8, response_address},
+    response::{HhdmResponse, RsdpResponse},
+    Error,
+};
+
+/// The size of the header shared by every system description table.
+pub const SDT_HEADER_SIZE: usize = 36;
+
+/// Split the entries of a table into `(type, entry)` pairs, where each entry
+/// starts with a type byte and a length byte.
+fn entries(bytes: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut rest = bytes;
+    core::iter::from_fn(move || {
+        let (&ty, &len) = (rest.first()?, rest.get(1)?);
+        if len < 2 || len as usize > rest.len() {
+            return None;
+        }
+        let (entry, next) = rest.split_at(len as usize);
+        rest = next;
+        Some((ty, entry))
+    })
+}
+
+/// The Generic Address Structure, which describes a register in some address
+/// space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenericAddress {
+    /// The address space: 0 for system memory, 1 for system I/O, 2 for PCI
+    /// configuration space, and so on.
+    pub address_space: u8,
+    /// The width of the register, in bits.
+    pub bit_width: u8,
+    /// The offset of the register within the address, in bits.
+    pub bit_offset: u8,
+    /// The access size: 0 for undefined, then 1 to 4 for byte, word, dword
+    /// and qword accesses.
+    pub access_size: u8,
+    /// The address of the register in its address space.
+    pub address: u64,
+}
+impl GenericAddress {
+    /// The system memory address space.
+    pub const SYSTEM_MEMORY: u8 = 0;
+    /// The system I/O address space.
+    pub const SYSTEM_IO: u8 = 1;
+
+    fn parse(bytes: &[u8], offset: usize) -> Option<Self> {
+        Some(Self {
+            address_space: read_u8(bytes, offset)?,
+            bit_width: read_u8(bytes, offset + 1)?,
+            bit_offset: read_u8(bytes, offset + 2)?,
+            access_size: read_u8(bytes, offset + 3)?,
+            address: read_u64(bytes, offset + 4)?,
+        })
+    }
+}
+
+/// The Root System Description Pointer.
+#[derive(Clone, Copy)]
+pub struct Rsdp<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> Rsdp<'a> {
+    /// The ACPI revision: 0 for ACPI 1.0, which only has an RSDT, or 2 and
+    /// above for later versions, which may have an XSDT.
+    pub fn revision(&self) -> u8 {
+        self.bytes[15]
+    }
+
+    /// The OEM ID.
+    pub fn oem_id(&self) -> [u8; 6] {
+        read(self.bytes, 9).unwrap()
+    }
+
+    /// The physical address of the RSDT.
+    pub fn rsdt_address(&self) -> u32 {
+        read_u32(self.bytes, 16).unwrap()
+    }
+
+    /// The physical address of the XSDT, if the revision is 2 or above and
+    /// the address is not null.
+    pub fn xsdt_address(&self) -> Option<u64> {
+        read_u64(self.bytes, 24).filter(|&address| address != 0)
+    }
+}
+
+/// A System Description Table, made of a common header followed by
+/// table-specific data.
+#[derive(Clone, Copy)]
+pub struct Sdt<'a> {
+    bytes: &'a [u8],
+}
+impl<'a> Sdt<'a> {
+    /// The signature of the table, such as `b"APIC"`.
+    pub fn signature(&self) -> [u8; 4] {
+        read(self.bytes, 0).unwrap()
+    }
+
+    /// The length of the table, including its header.
+    pub fn length(&self) -> u32 {
+        read_u32(self.bytes, 4).unwrap()
+    }
+
+    /// The revision of the table's structure.
+    pub fn revision(&self) -> u8 {
+        self.bytes[8]
+    }
+
+    /// The OEM ID.
+    pub fn oem_id(&self) -> [u8; 6] {
+        read(self.bytes, 10).unwrap()
+    }
+
+    /// The OEM's ID for the table.
+    pub fn oem_table_id(&self) -> [u8; 8] {
+        read(self.bytes, 16).unwrap()
+    }
+
+    /// The OEM's revision of the table.
+    pub fn oem_revision(&self) -> u32 {
+        read_u32(self.bytes, 24).unwrap()
+    }
+
+    /// The ID of the tool which created the table.
+    pub fn creator_id(&self) -> [u8; 4] {
+        read(self.bytes, 28).unwrap()
+    }
+
+    /// The revision of the tool which created the table.
+    pub fn creator_revision(&self) -> u32 {
+        read_u32(self.bytes, 32).unwrap()
+    }
+
+    /// The whole table, including its header.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The table-specific data following the header.
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[SDT_HEADER_SIZE..]
+    }
+}
+
+/// The ACPI tables, reached through the higher-half direct map.
+#[derive(Clone, Copy)]
+pub struct Acpi<'a> {
+    hhdm_offset: u64,
+    rsdp: Rsdp<'a>,
+    root: Sdt<'a>,
+    _marker: PhantomData<&'a [u8]>,
+}
+impl<'a> Acpi<'a> {
+    /// Validate the RSDP at `rsdp` and its root table: the XSDT if there is
+    /// one, or the RSDT otherwise.
+    ///
+    /// # Safety
+    /// `hhdm_offset` must be the offset of a direct map covering the RSDP and
+    /// every ACPI table, such as the one returned by
+    /// [`HhdmResponse::offset`], and the tables must not be modified during
+    /// `'a`.
+    pub unsafe fn new(rsdp: PhysAddr, hhdm_offset: u64) -> Result<Self, Error> {
+        let bytes = map(rsdp, hhdm_offset, 20)?;
+        if bytes[..8] != *b"RSD PTR " {
+            return Err(Error::InvalidSignature);
+        }
+        if !checksum(bytes) {
+            return Err(Error::InvalidChecksum);
+        }
+        let bytes = match bytes[15] {
+            0 => bytes,
+            _ => {
+                let length = read_u32(map(rsdp, hhdm_offset, 24)?, 20).unwrap();
+                if length < 36 {
+                    return Err(Error::InvalidLength);
+                }
+                let bytes = map(rsdp, hhdm_offset, length as usize)?;
+                if !checksum(bytes) {
+                    return Err(Error::InvalidChecksum);
+                }
+                bytes
+            }
+        };
+        let rsdp = Rsdp { bytes };
+
+        let root = match rsdp.xsdt_address() {
+            Some(address) => table(PhysAddr::new(address), hhdm_offset, Some(b"XSDT"))?,
+            None => table(
+                PhysAddr::new(rsdp.rsdt_address().into()),
+                hhdm_offset,
+                Some(b"RSDT"),
+            )?,
+        };
+        Ok(Self {
+            hhdm_offset,
+            rsdp,
+            root,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Validate the RSDP given by the bootloader. This accepts both the
+    /// physical address returned from base revision 3, and the higher-half
+    /// direct map address returned by earlier base revisions.
+    ///
+    /// # Safety
+    /// See [`new`](Self::new).
+    pub unsafe fn from_response(rsdp: &RsdpResponse, hhdm: &HhdmResponse) -> Result<Self, Error> {
+        Self::new(response_address(rsdp.address() as u64, hhdm), hhdm.offset())
+    }
+
+    /// The RSDP.
+    pub fn rsdp(&self) -> Rsdp<'a> {
+        self.rsdp
+    }
+
+    /// The root table: the XSDT, or the RSDT if there is no XSDT.
+    pub fn root(&self) -> Sdt<'a> {
+        self.root
+    }
+
+    /// Read and validate the table at a physical address, such as the DSDT
+    /// address from the [FADT](Fadt::dsdt_address).
+    ///
+    /// # Safety
+    /// As for [`new`](Self::new), the direct map must cover the table at
+    /// `address`, and the table must not be modified during `'a`.
+    pub unsafe fn table_at(&self, address: PhysAddr) -> Result<Sdt<'a>, Error> {
+        table(address, self.hhdm_offset, None)
+    }
+
+    /// Returns every table listed in the root table, in order. Tables which
+    /// fail validation are returned as errors, so that they can be reported.
+    pub fn tables(&self) -> impl Iterator<Item = Result<Sdt<'a>, Error>> + 'a {
+        let acpi = *self;
+        let entry_size = match &self.root.signature() {
+            b"XSDT" => size_of::<u64>(),
+            _ => size_of::<u32>(),
+        };
+        self.root.data().chunks_exact(entry_size).map(move |entry| {
+            let mut address = [0; 8];
+            address[..entry_size].copy_from_slice(entry);
+            // SAFETY: `new` requires the direct map to cover every table
+            unsafe { acpi.table_at(PhysAddr::new(u64::from_le_bytes(address))) }
+        })
+    }
+
+    /// Find the first valid table with the given signature.
+    pub fn find(&self, signature: &[u8; 4]) -> Option<Sdt<'a>> {
+        self.tables()
+            .flatten()
+            .find(|table| table.signature() == *signature)
+    }
+
+    /// Find the MADT (signature `APIC`).
+    pub fn madt(&self) -> Option<Madt<'a>> {
+        Madt::new(self.find(b"APIC")?)
+    }
+
+    /// Find the FADT (signature `FACP`).
+    pub fn fadt(&self) -> Option<Fadt<'a>> {
+        Some(Fadt(self.find(b"FACP")?))
+    }
+
+    /// Find the DSDT, through the FADT.
+    pub fn dsdt(&self) -> Option<Sdt<'a>> {
+        let address = self.fadt()?.dsdt_address()?;
+        // SAFETY: `new` requires the direct map to cover every table
+        unsafe { self.table_at(PhysAddr::new(address)) }
+            .ok()
+            .filter(|table| table.signature() == *b"DSDT")
+    }
+
+    /// Find the HPET table.
+    pub fn hpet(&self) -> Option<Hpet<'a>> {
+        Hpet::new(self.find(b"HPET")?)
+    }
+
+    /// Find the MCFG.
+    pub fn mcfg(&self) -> Option<Mcfg<'a>> {
+        Mcfg::new(self.find(b"MCFG")?)
+    }
+
+    /// Find the SRAT.
+    pub fn srat(&self) -> Option<Srat<'a>> {
+        Srat::new(self.find(b"SRAT")?)
+    }
+}
+
+/// Read and validate the table at a physical address.
+unsafe fn table<'a>(
+    phys: PhysAddr,
+    hhdm_offset: u64,
+    signature: Option<&[u8; 4]>,
+) -> Result<Sdt<'a>, Error> {
+    let header = map(phys, hhdm_offset, SDT_HEADER_SIZE)?;
+    if signature.is_some_and(|signature| header[..4] != *signature) {
+        return Err(Error::InvalidSignature);
+    }
+    let length = read_u32(header, 4).unwrap() as usize;
+    if length < SDT_HEADER_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    let bytes = map(phys, hhdm_offset, length)?;
+    if !checksum(bytes) {
+        return Err(Error::InvalidChecksum);
+    }
+    Ok(Sdt { bytes })
+}
+
+/// An entry of the [MADT](Madt).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MadtEntry<'a> {
+    /// A processor with a local APIC.
+    LocalApic {
+        /// The ACPI processor UID.
+        processor_id: u8,
+        /// The local APIC ID.
+        apic_id: u8,
+        /// Bit 0 is set if the processor is enabled, and bit 1 if it can be
+        /// brought online.
+        flags: u32,
+    },
+    /// An I/O APIC.
+    IoApic {
+        /// The I/O APIC ID.
+        id: u8,
+        /// The physical address of the I/O APIC's registers.
+        address: u32,
+        /// The first global system interrupt handled by the I/O APIC.
+        gsi_base: u32,
+    },
+    /// A mapping of an ISA interrupt to a global system interrupt.
+    InterruptSourceOverride {
+        /// The bus, which is always 0 for ISA.
+        bus: u8,
+        /// The ISA IRQ.
+        source: u8,
+        /// The global system interrupt it is connected to.
+        gsi: u32,
+        /// The polarity and trigger mode of the interrupt.
+        flags: u16,
+    },
+    /// A global system interrupt which should be a non-maskable interrupt.
+    NmiSource {
+        /// The polarity and trigger mode of the interrupt.
+        flags: u16,
+        /// The global system interrupt.
+        gsi: u32,
+    },
+    /// A local APIC pin connected to a non-maskable interrupt.
+    LocalApicNmi {
+        /// The ACPI processor UID, or `0xff` for all processors.
+        processor_id: u8,
+        /// The polarity and trigger mode of the interrupt.
+        flags: u16,
+        /// The local APIC LINT pin.
+        lint: u8,
+    },
+    /// A 64-bit address of the local APIC, replacing the one in the MADT
+    /// header.
+    LocalApicAddressOverride {
+        /// The physical address of the local APIC.
+        address: u64,
+    },
+    /// A processor with a local x2APIC.
+    LocalX2Apic {
+        /// The x2APIC ID.
+        x2apic_id: u32,
+        /// See [`MadtEntry::LocalApic::flags`].
+        flags: u32,
+        /// The ACPI processor UID.
+        processor_uid: u32,
+    },
+    /// An entry not decoded by this crate, or too short for its type.
+    Other {
+        /// The type of the entry.
+        ty: u8,
+        /// The whole entry, including its type and length bytes.
+        bytes: &'a [u8],
+    },
+}
+impl<'a> MadtEntry<'a> {
+    fn parse(ty: u8, e: &'a [u8]) -> Self {
+        let entry = || {
+            Some(match ty {
+                0 => Self::LocalApic {
+                    processor_id: read_u8(e, 2)?,
+                    apic_id: read_u8(e, 3)?,
+                    flags: read_u32(e, 4)?,
+                },
+                1 => Self::IoApic {
+                    id: read_u8(e, 2)?,
+                    address: read_u32(e, 4)?,
+                    gsi_base: read_u32(e, 8)?,
+                },
+                2 => Self::InterruptSourceOverride {
+                    bus: read_u8(e, 2)?,
+                    source: read_u8(e, 3)?,
+                    gsi: read_u32(e, 4)?,
+                    flags: read_u16(e, 8)?,
+                },
+                3 => Self::NmiSource {
+                    flags: read_u16(e, 2)?,
+                    gsi: read_u32(e, 4)?,
+                },
+                4 => Self::LocalApicNmi {
+                    processor_id: read_u8(e, 2)?,
+                    flags: read_u16(e, 3)?,
+                    lint: read_u8(e, 5)?,
+                },
+                5 => Self::LocalApicAddressOverride {
+                    address: read_u64(e, 4)?,
+                },
+                9 => Self::LocalX2Apic {
+                    x2apic_id: read_u32(e, 4)?,
+                    flags: read_u32(e, 8)?,
+                    processor_uid: read_u32(e, 12)?,
+                },
+                _ => return None,
+            })
+        };
+        entry().unwrap_or(Self::Other { ty, bytes: e })
+    }
+}
+
+/// The Multiple APIC Description Table, listing the interrupt controllers.
+#[derive(Clone, Copy)]
+pub struct Madt<'a>(Sdt<'a>);
+impl<'a> Madt<'a> {
+    fn new(sdt: Sdt<'a>) -> Option<Self> {
+        (sdt.data().len() >= 8).then_some(Self(sdt))
+    }
+
+    /// The underlying table.
+    pub fn sdt(&self) -> Sdt<'a> {
+        self.0
+    }
+
+    /// The 32-bit physical address of the local APIC. This may be replaced by
+    /// a [`MadtEntry::LocalApicAddressOverride`].
+    pub fn local_apic_address(&self) -> u32 {
+        read_u32(self.0.data(), 0).unwrap()
+    }
+
+    /// The MADT flags. Bit 0 is set if the system also has dual 8259 PICs,
+    /// which must be masked before using the APICs.
+    pub fn flags(&self) -> u32 {
+        read_u32(self.0.data(), 4).unwrap()
+    }
+
+    /// Returns the entries of the MADT.
+    pub fn entries(&self) -> impl Iterator<Item = MadtEntry<'a>> + 'a {
+        entries(&self.0.data()[8..]).map(|(ty, entry)| MadtEntry::parse(ty, entry))
+    }
+}
+
+/// The Fixed ACPI Description Table, describing fixed hardware features.
+///
+/// Fields which were added in later revisions return `None` if the table is
+/// too short to hold them.
+#[derive(Clone, Copy)]
+pub struct Fadt<'a>(Sdt<'a>);
+impl<'a> Fadt<'a> {
+    /// The underlying table.
+    pub fn sdt(&self) -> Sdt<'a> {
+        self.0
+    }
+
+    /// The physical address of the DSDT, preferring the 64-bit `X_DSDT` field
+    /// when it is present and not null.
+    pub fn dsdt_address(&self) -> Option<u64> {
+        let bytes = self.0.bytes();
+        read_u64(bytes, 140)
+            .filter(|&address| address != 0)
+            .or_else(|| read_u32(bytes, 40).map(u64::from))
+            .filter(|&address| address != 0)
+    }
+
+    /// The preferred power management profile, such as 1 for desktops and 2
+    /// for mobile systems.
+    pub fn preferred_pm_profile(&self) -> Option<u8> {
+        read_u8(self.0.bytes(), 45)
+    }
+
+    /// The interrupt the SCI is wired to.
+    pub fn sci_interrupt(&self) -> Option<u16> {
+        read_u16(self.0.bytes(), 46)
+    }
+
+    /// The I/O port of the SMI command register, or 0 if the system has no
+    /// SMM or is always in ACPI mode.
+    pub fn smi_command_port(&self) -> Option<u32> {
+        read_u32(self.0.bytes(), 48)
+    }
+
+    /// The value to write to the SMI command port to enable ACPI mode.
+    pub fn acpi_enable(&self) -> Option<u8> {
+        read_u8(self.0.bytes(), 52)
+    }
+
+    /// The value to write to the SMI command port to disable ACPI mode.
+    pub fn acpi_disable(&self) -> Option<u8> {
+        read_u8(self.0.bytes(), 53)
+    }
+
+    /// The index of the century in the RTC's CMOS memory, or 0 if it is not
+    /// supported.
+    pub fn century(&self) -> Option<u8> {
+        read_u8(self.0.bytes(), 108)
+    }
+
+    /// The IA-PC boot architecture flags. Bit 0 is set if there are legacy
+    /// devices, bit 1 if there is an 8042 controller, bit 2 if VGA must not
+    /// be probed and bit 3 if MSI must not be enabled.
+    pub fn iapc_boot_arch(&self) -> Option<u16> {
+        read_u16(self.0.bytes(), 109)
+    }
+
+    /// The fixed feature flags.
+    pub fn flags(&self) -> Option<u32> {
+        read_u32(self.0.bytes(), 112)
+    }
+
+    /// The reset register, written with [`reset_value`](Self::reset_value) to
+    /// reset the system. Only valid if bit 10 of the [flags](Self::flags) is
+    /// set.
+    pub fn reset_register(&self) -> Option<GenericAddress> {
+        GenericAddress::parse(self.0.bytes(), 116)
+    }
+
+    /// The value to write to the [reset register](Self::reset_register).
+    pub fn reset_value(&self) -> Option<u8> {
+        read_u8(self.0.bytes(), 128)
+    }
+
+    /// The ARM boot architecture flags. Bit 0 is set if PSCI is implemented,
+    /// and bit 1 if it must be called with HVC rather than SMC.
+    pub fn arm_boot_arch(&self) -> Option<u16> {
+        read_u16(self.0.bytes(), 129)
+    }
+}
+
+/// The HPET Description Table.
+#[derive(Clone, Copy)]
+pub struct Hpet<'a>(Sdt<'a>);
+impl<'a> Hpet<'a> {
+    fn new(sdt: Sdt<'a>) -> Option<Self> {
+        (sdt.data().len() >= 20).then_some(Self(sdt))
+    }
+
+    /// The underlying table.
+    pub fn sdt(&self) -> Sdt<'a> {
+        self.0
+    }
+
+    /// The event timer block ID, which mirrors the capabilities register.
+    pub fn event_timer_block_id(&self) -> u32 {
+        read_u32(self.0.data(), 0).unwrap()
+    }
+
+    /// The number of comparators in the first timer block.
+    pub fn comparator_count(&self) -> u8 {
+        ((self.event_timer_block_id() >> 8) & 0x1f) as u8 + 1
+    }
+
+    /// Returns whether the main counter is 64 bits wide.
+    pub fn counter_64bit(&self) -> bool {
+        self.event_timer_block_id() & (1 << 13) != 0
+    }
+
+    /// Returns whether the HPET can replace the legacy PIT and RTC interrupts.
+    pub fn legacy_replacement_capable(&self) -> bool {
+        self.event_timer_block_id() & (1 << 15) != 0
+    }
+
+    /// The PCI vendor ID of the HPET.
+    pub fn pci_vendor_id(&self) -> u16 {
+        (self.event_timer_block_id() >> 16) as u16
+    }
+
+    /// The address of the HPET's registers.
+    pub fn base_address(&self) -> GenericAddress {
+        GenericAddress::parse(self.0.data(), 4).unwrap()
+    }
+
+    /// The sequence number of the HPET.
+    pub fn hpet_number(&self) -> u8 {
+        self.0.data()[16]
+    }
+
+    /// The minimum number of ticks which must be used for periodic mode
+    /// without losing interrupts.
+    pub fn minimum_tick(&self) -> u16 {
+        read_u16(self.0.data(), 17).unwrap()
+    }
+}
+
+/// An entry of the [MCFG](Mcfg), describing one PCI Express enhanced
+/// configuration space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct McfgEntry {
+    /// The physical address of the configuration space.
+    pub base_address: u64,
+    /// The PCI segment group.
+    pub segment: u16,
+    /// The first bus decoded by the configuration space.
+    pub start_bus: u8,
+    /// The last bus decoded by the configuration space.
+    pub end_bus: u8,
+}
+
+/// The PCI Express memory-mapped configuration space table.
+#[derive(Clone, Copy)]
+pub struct Mcfg<'a>(Sdt<'a>);
+impl<'a> Mcfg<'a> {
+    fn new(sdt: Sdt<'a>) -> Option<Self> {
+        (sdt.data().len() >= 8).then_some(Self(sdt))
+    }
+
+    /// The underlying table.
+    pub fn sdt(&self) -> Sdt<'a> {
+        self.0
+    }
+
+    /// Returns the configuration spaces.
+    pub fn entries(&self) -> impl Iterator<Item = McfgEntry> + 'a {
+        self.0.data()[8..].chunks_exact(16).map(|e| McfgEntry {
+            base_address: read_u64(e, 0).unwrap(),
+            segment: read_u16(e, 8).unwrap(),
+            start_bus: e[10],
+            end_bus: e[11],
+        })
+    }
+}
+
+/// An entry of the [SRAT](Srat).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SratEntry<'a> {
+    /// The proximity domain of a processor with a local APIC.
+    LocalApicAffinity {
+        /// The proximity domain.
+        proximity_domain: u32,
+        /// The local APIC ID.
+        apic_id: u8,
+        /// Bit 0 is set if the entry is enabled.
+        flags: u32,
+    },
+    /// The proximity domain of a range of memory.
+    MemoryAffinity {
+        /// The proximity domain.
+        proximity_domain: u32,
+        /// The physical address of the range.
+        base: u64,
+        /// The size of the range, in bytes.
+        length: u64,
+        /// Bit 0 is set if the entry is enabled, bit 1 if the memory is
+        /// hot-pluggable and bit 2 if it is non-volatile.
+        flags: u32,
+    },
+    /// The proximity domain of a processor with a local x2APIC.
+    X2ApicAffinity {
+        /// The proximity domain.
+        proximity_domain: u32,
+        /// The x2APIC ID.
+        x2apic_id: u32,
+        /// Bit 0 is set if the entry is enabled.
+        flags: u32,
+    },
+    /// The proximity domain of a processor with a GIC CPU interface.
+    GiccAffinity {
+        /// The proximity domain.
+        proximity_domain: u32,
+        /// The ACPI processor UID.
+        processor_uid: u32,
+        /// Bit 0 is set if the entry is enabled.
+        flags: u32,
+    },
+    /// An entry not decoded by this crate, or too short for its type.
+    Other {
+        /// The type of the entry.
+        ty: u8,
+        /// The whole entry, including its type and length bytes.
+        bytes: &'a [u8],
+    },
+}
+impl<'a> SratEntry<'a> {
+    fn parse(ty: u8, e: &'a [u8]) -> Self {
+        let entry = || {
+            Some(match ty {
+                0 => Self::LocalApicAffinity {
+                    proximity_domain: u32::from_le_bytes([
+                        read_u8(e, 2)?,
+                        read_u8(e, 9)?,
+                        read_u8(e, 10)?,
+                        read_u8(e, 11)?,
+                    ]),
+                    apic_id: read_u8(e, 3)?,
+                    flags: read_u32(e, 4)?,
+                },
+                1 => Self::MemoryAffinity {
+                    proximity_domain: read_u32(e, 2)?,
+                    base: read_u64(e, 8)?,
+                    length: read_u64(e, 16)?,
+                    flags: read_u32(e, 28)?,
+                },
+                2 => Self::X2ApicAffinity {
+                    proximity_domain: read_u32(e, 4)?,
+                    x2apic_id: read_u32(e, 8)?,
+                    flags: read_u32(e, 12)?,
+                },
+                3 => Self::GiccAffinity {
+                    proximity_domain: read_u32(e, 2)?,
+                    processor_uid: read_u32(e, 6)?,
+                    flags: read_u32(e, 10)?,
+                },
+                _ => return None,
+            })
+        };
+        entry().unwrap_or(Self::Other { ty, bytes: e })
+    }
+}
+
+/// The System Resource Affinity Table, assigning processors and memory to
+/// NUMA proximity domains.
+#[derive(Clone, Copy)]
+pub struct Srat<'a>(Sdt<'a>);
+impl<'a> Srat<'a> {
+    fn new(sdt: Sdt<'a>) -> Option<Self> {
+        (sdt.data().len() >= 12).then_some(Self(sdt))
+    }
+
+    /// The underlying table.
+    pub fn sdt(&self) -> Sdt<'a> {
+        self.0
+    }
+
+    /// Returns the entries of the SRAT.
+    pub fn entries(&self) -> impl Iterator<Item = SratEntry<'a>> + 'a {
+        entries(&self.0.data()[12..]).map(|(ty, entry)| SratEntry::parse(ty, entry))
+    }
+}
@@ -0,0 +1,138 @@
+//! Physical and virtual address types.
+//!
+//! These are thin wrappers around `u64` that keep the two address spaces
+//! apart. Use [`HhdmResponse`](crate::response::HhdmResponse) to convert
+//! between them through the higher-half direct map.
+
+use core::{fmt, num::NonZeroUsize};
+
+macro_rules! addr_type {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        pub struct $name(u64);
+        impl $name {
+            /// Create a new address.
+            pub const fn new(addr: u64) -> Self {
+                Self(addr)
+            }
+
+            /// Returns the address as a `u64`.
+            pub const fn as_u64(self) -> u64 {
+                self.0
+            }
+
+            /// Add `offset` bytes to the address, returning `None` on overflow.
+            pub const fn checked_add(self, offset: u64) -> Option<Self> {
+                match self.0.checked_add(offset) {
+                    Some(addr) => Some(Self(addr)),
+                    None => None,
+                }
+            }
+
+            /// Subtract `offset` bytes from the address, returning `None` on
+            /// overflow.
+            pub const fn checked_sub(self, offset: u64) -> Option<Self> {
+                match self.0.checked_sub(offset) {
+                    Some(addr) => Some(Self(addr)),
+                    None => None,
+                }
+            }
+
+            /// Returns whether the address is a multiple of `align`, which
+            /// must be a power of two.
+            pub const fn is_aligned(self, align: u64) -> bool {
+                self.0 & (align - 1) == 0
+            }
+        }
+        impl From<u64> for $name {
+            fn from(addr: u64) -> Self {
+                Self(addr)
+            }
+        }
+        impl From<usize> for $name {
+            fn from(addr: usize) -> Self {
+                Self(addr as u64)
+            }
+        }
+        impl From<NonZeroUsize> for $name {
+            fn from(addr: NonZeroUsize) -> Self {
+                Self(addr.get() as u64)
+            }
+        }
+        impl From<$name> for u64 {
+            fn from(addr: $name) -> Self {
+                addr.0
+            }
+        }
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($name), "({:#x})"), self.0)
+            }
+        }
+        impl fmt::LowerHex for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::LowerHex::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+addr_type!(
+    /// An address in *physical space*.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limine::addr::PhysAddr;
+    ///
+    /// let addr = PhysAddr::new(0x1000);
+    /// assert_eq!(addr.checked_add(0x20), Some(PhysAddr::new(0x1020)));
+    /// assert_eq!(PhysAddr::new(u64::MAX).checked_add(1), None);
+    /// assert!(addr.is_aligned(0x1000));
+    /// ```
+    PhysAddr
+);
+
+impl PhysAddr {
+    /// Returns the address of `self` in a direct map at `offset`, or `None`
+    /// on overflow.
+    pub(crate) const fn to_virt(self, offset: u64) -> Option<VirtAddr> {
+        match self.0.checked_add(offset) {
+            Some(addr) => Some(VirtAddr(addr)),
+            None => None,
+        }
+    }
+}
+
+addr_type!(
+    /// An address in *virtual space*.
+    VirtAddr
+);
+
+impl VirtAddr {
+    /// Returns the physical address of `self`, in a direct map at `offset`, or
+    /// `None` if it lies below the direct map.
+    pub(crate) const fn to_phys(self, offset: u64) -> Option<PhysAddr> {
+        match self.0.checked_sub(offset) {
+            Some(addr) => Some(PhysAddr(addr)),
+            None => None,
+        }
+    }
+
+    /// Returns the address as a pointer, or `None` if it does not fit in a
+    /// `usize`.
+    pub fn as_ptr<T>(self) -> Option<*mut T> {
+        usize::try_from(self.0).ok().map(|addr| addr as *mut T)
+    }
+}
+impl<T> From<*const T> for VirtAddr {
+    fn from(ptr: *const T) -> Self {
+        Self(ptr as usize as u64)
+    }
+}
+impl<T> From<*mut T> for VirtAddr {
+    fn from(ptr: *mut T) -> Self {
+        Self(ptr as usize as u64)
+    }
+}
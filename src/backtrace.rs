@@ -0,0 +1,241 @@
+//! Symbolized backtraces for panic handlers, using the symbol table of the
+//! executable's own [file](crate::response::ExecutableFileResponse).
+//!
+//! A [`Symbolizer`] maps instruction addresses back to the function containing
+//! them, accounting for where the executable was actually loaded. [`Frames`]
+//! walks the stack using frame pointers, so the executable must be built with
+//! `-C force-frame-pointers=yes` for it to find more than the first frame.
+//!
+//! With the `demangle` feature, symbol names are demangled when displayed.
+//!
+//! # Examples
+//! ```rust,no_run
+//! use core::fmt::Write;
+//! use limine::{
+//!     backtrace::{Frames, Symbolizer},
+//!     request::{ExecutableAddressRequest, ExecutableFileRequest},
+//! };
+//!
+//! static EXECUTABLE_FILE_REQUEST: ExecutableFileRequest = ExecutableFileRequest::new();
+//! static EXECUTABLE_ADDRESS_REQUEST: ExecutableAddressRequest = ExecutableAddressRequest::new();
+//!
+//! fn print_backtrace(out: &mut impl Write) -> core::fmt::Result {
+//!     let file = EXECUTABLE_FILE_REQUEST.get_response().unwrap();
+//!     let address = EXECUTABLE_ADDRESS_REQUEST.get_response().unwrap();
+//!     let symbolizer = Symbolizer::from_responses(file, address).unwrap();
+//!
+//!     // SAFETY: the executable is built with frame pointers
+//!     for (i, addr) in unsafe { Frames::current() }.enumerate() {
+//!         match symbolizer.resolve(addr) {
+//!             Some(symbol) => writeln!(out, "{i:>3}: {addr:#018x} {symbol}")?,
+//!             None => writeln!(out, "{i:>3}: {addr:#018x} <unknown>")?,
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use core::fmt;
+
+use crate::{
+    elf::{Elf, SymbolTable, PT_LOAD},
+    memory_map::PAGE_SIZE,
+    response::{ExecutableAddressResponse, ExecutableFileResponse},
+    Error,
+};
+
+/// The most frames [`Frames`] will return, in case the stack is corrupted in a
+/// way that still looks like a valid chain.
+pub const MAX_FRAMES: usize = 128;
+
+/// Maps instruction addresses to symbols.
+#[derive(Clone, Copy)]
+pub struct Symbolizer<'a> {
+    symbols: SymbolTable<'a>,
+    slide: u64,
+    physical_to_virtual: Option<u64>,
+}
+impl<'a> Symbolizer<'a> {
+    /// Symbolize addresses in an image loaded `slide` bytes above the
+    /// addresses it was linked at. Use a slide of 0 if the executable was not
+    /// relocated.
+    pub fn new(elf: &Elf<'a>, slide: u64) -> Result<Self, Error> {
+        Ok(Self {
+            symbols: elf.symbols().ok_or(Error::NoSymbols)?,
+            slide,
+            physical_to_virtual: None,
+        })
+    }
+
+    /// Symbolize addresses in the running executable. The slide is the
+    /// difference between [`ExecutableAddressResponse::virtual_base`] and the
+    /// link address of the first loaded segment, which covers both relocation
+    /// by the bootloader and KASLR.
+    pub fn from_responses(
+        file: &'a ExecutableFileResponse,
+        address: &ExecutableAddressResponse,
+    ) -> Result<Self, Error> {
+        let elf = Elf::from_file(file.file())?;
+        let link_base = elf
+            .program_headers()
+            .filter(|ph| ph.ty == PT_LOAD)
+            .map(|ph| ph.vaddr & !(PAGE_SIZE - 1))
+            .min()
+            .unwrap_or(0);
+        let mut symbolizer = Self::new(&elf, address.virtual_base().wrapping_sub(link_base))?;
+        symbolizer.physical_to_virtual =
+            Some(address.virtual_base().wrapping_sub(address.physical_base()));
+        Ok(symbolizer)
+    }
+
+    /// The difference between the addresses the executable was loaded at and
+    /// the addresses it was linked at.
+    pub fn slide(&self) -> u64 {
+        self.slide
+    }
+
+    /// Find the function containing the runtime address `addr`.
+    ///
+    /// Return addresses point just past the call instruction, which may be
+    /// past the end of a function that never returns. This looks up
+    /// `addr - 1` so that such calls are attributed to the caller; the
+    /// returned offset is still relative to `addr`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limine::{backtrace::Symbolizer, elf::Elf};
+    ///
+    /// let image = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    /// let elf = Elf::new(&image).unwrap();
+    /// let main = elf.symbols().unwrap().iter().find(|sym| sym.name == "main").unwrap();
+    ///
+    /// // Pretend the executable was loaded 1 MiB above its link address
+    /// let symbolizer = Symbolizer::new(&elf, 0x100000).unwrap();
+    /// let symbol = symbolizer.resolve(main.value + 0x100004).unwrap();
+    /// assert_eq!(symbol.name, "main");
+    /// assert_eq!(symbol.offset, 4);
+    /// assert_eq!(symbol.to_string(), "main+0x4");
+    /// ```
+    pub fn resolve(&self, addr: u64) -> Option<Symbol<'a>> {
+        let link_addr = addr.wrapping_sub(self.slide);
+        let symbol = self.symbols.containing(link_addr.wrapping_sub(1))?;
+        Some(Symbol {
+            name: symbol.name,
+            address: symbol.value.wrapping_add(self.slide),
+            offset: link_addr.wrapping_sub(symbol.value),
+        })
+    }
+
+    /// Like [`resolve`](Self::resolve), but for a physical address within
+    /// the executable. Returns `None` if the symbolizer was not created with
+    /// [`from_responses`](Self::from_responses).
+    pub fn resolve_physical(&self, addr: u64) -> Option<Symbol<'a>> {
+        self.resolve(addr.wrapping_add(self.physical_to_virtual?))
+    }
+}
+
+/// A resolved address, as returned by [`Symbolizer::resolve`].
+///
+/// It is displayed as `name+0xoffset`, with the name demangled if the
+/// `demangle` feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    /// The name of the symbol, as stored in the executable.
+    pub name: &'a str,
+    /// The runtime address of the start of the symbol.
+    pub address: u64,
+    /// The offset of the resolved address from the start of the symbol.
+    pub offset: u64,
+}
+impl fmt::Display for Symbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "demangle")]
+        write!(f, "{:#}", rustc_demangle::demangle(self.name))?;
+        #[cfg(not(feature = "demangle"))]
+        f.write_str(self.name)?;
+        write!(f, "+{:#x}", self.offset)
+    }
+}
+
+/// An iterator over the return addresses on the stack, found by following the
+/// chain of frame pointers.
+///
+/// The walk stops at a null or misaligned frame pointer, at a frame pointer
+/// which does not move up the stack, or after [`MAX_FRAMES`] frames.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))] {
+/// use limine::backtrace::Frames;
+///
+/// // Two frames laid out as on x86-64 and AArch64: the saved frame pointer,
+/// // followed by the return address
+/// let mut stack = [0u64; 4];
+/// let base = stack.as_ptr() as u64;
+/// stack[0] = base + 16;
+/// stack[1] = 0x1234;
+/// stack[2] = 0;
+/// stack[3] = 0x5678;
+///
+/// let frames = unsafe { Frames::new(base as usize) };
+/// assert!(frames.eq([0x1234, 0x5678]));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Frames {
+    fp: usize,
+    depth: usize,
+}
+impl Frames {
+    /// Walk the stack starting from the frame pointer `fp`.
+    ///
+    /// # Safety
+    /// `fp` must be null or a valid frame pointer, and every frame in the
+    /// chain must have been built with frame pointers.
+    pub unsafe fn new(fp: usize) -> Self {
+        Self { fp, depth: 0 }
+    }
+
+    /// Walk the stack starting from the caller's frame.
+    ///
+    /// # Safety
+    /// Every frame in the chain must have been built with frame pointers.
+    #[inline(always)]
+    #[cfg(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    ))]
+    pub unsafe fn current() -> Self {
+        let fp: usize;
+        #[cfg(target_arch = "x86_64")]
+        core::arch::asm!("mov {}, rbp", out(reg) fp, options(nomem, nostack));
+        #[cfg(target_arch = "aarch64")]
+        core::arch::asm!("mov {}, x29", out(reg) fp, options(nomem, nostack));
+        #[cfg(target_arch = "riscv64")]
+        core::arch::asm!("mv {}, s0", out(reg) fp, options(nomem, nostack));
+        Self::new(fp)
+    }
+}
+impl Iterator for Frames {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        const WORD: usize = core::mem::size_of::<usize>();
+        if self.fp == 0 || self.fp % WORD != 0 || self.depth >= MAX_FRAMES {
+            return None;
+        }
+        // On x86-64 and AArch64 the frame pointer points at the saved frame
+        // pointer, followed by the return address. On RISC-V and LoongArch it
+        // points just past them.
+        #[cfg(any(target_arch = "riscv64", target_arch = "loongarch64"))]
+        let record = self.fp.checked_sub(2 * WORD)? as *const usize;
+        #[cfg(not(any(target_arch = "riscv64", target_arch = "loongarch64")))]
+        let record = self.fp as *const usize;
+        let (next, ret) = unsafe { (*record, *record.add(1)) };
+
+        self.depth += 1;
+        self.fp = if next > self.fp { next } else { 0 };
+        (ret != 0).then_some(ret as u64)
+    }
+}
@@ -0,0 +1,441 @@
+//! Owned copies of responses, for use after bootloader memory is reclaimed.
+//!
+//! Every response lives in [bootloader-reclaimable
+//! memory](crate::memory_map::EntryType::BOOTLOADER_RECLAIMABLE), so any
+//! reference obtained from a request dangles once that memory is reused.
+//! [`BootInfo`] deep-copies the answered responses onto the heap, after which
+//! the reclaimable regions can safely be handed to the physical memory
+//! allocator.
+//!
+//! Memory that the responses merely point to, such as module contents, the
+//! framebuffer and firmware tables, is not copied, since it does not live in
+//! bootloader-reclaimable memory.
+//!
+//! This module is only available with the `alloc` feature.
+//!
+//! # Examples
+//! ```rust
+//! # #[cfg(feature = "mock")] {
+//! use limine::{
+//!     boot_info::BootInfo,
+//!     memory_map::{Entry, EntryType},
+//!     request::{ExecutableCmdlineRequest, MemoryMapRequest},
+//! };
+//!
+//! static MEMORY_MAP_REQUEST: MemoryMapRequest = MemoryMapRequest::new();
+//! static EXECUTABLE_CMDLINE_REQUEST: ExecutableCmdlineRequest = ExecutableCmdlineRequest::new();
+//! # unsafe {
+//! #     MEMORY_MAP_REQUEST.set_response(Some(limine::mock::memory_map(&[Entry {
+//! #         base: 0x1000,
+//! #         length: 0x9f000,
+//! #         entry_type: EntryType::USABLE,
+//! #     }])));
+//! #     EXECUTABLE_CMDLINE_REQUEST.set_response(Some(limine::mock::executable_cmdline("quiet")));
+//! # }
+//!
+//! // Once a heap is available, copy everything that will be needed later...
+//! let info = BootInfo::new()
+//!     .with_memory_map(MEMORY_MAP_REQUEST.get_response())
+//!     .with_executable_cmdline(EXECUTABLE_CMDLINE_REQUEST.get_response());
+//!
+//! // ...after which bootloader-reclaimable memory may be reused.
+//! assert!(info.memory_map.unwrap()[0].entry_type == EntryType::USABLE);
+//! assert_eq!(info.executable_cmdline.unwrap().to_bytes(), b"quiet");
+//! # }
+//! ```
+
+use alloc::{ffi::CString, string::String, vec::Vec};
+use core::{
+    ffi::c_char,
+    num::{NonZeroU32, NonZeroUsize},
+    time::Duration,
+};
+
+use crate::{
+    efi, error,
+    file::{self, MediaType, Uuid},
+    firmware_type::FirmwareType,
+    framebuffer::{self, VideoMode},
+    memory_map, paging,
+    response::*,
+    Error,
+};
+
+fn owned_string(ptr: *const c_char) -> String {
+    String::from_utf8_lossy(owned_c_string(ptr).as_bytes()).into_owned()
+}
+
+/// Copy a bootloader string, or return an empty string if it is null or has
+/// no NUL terminator within [`MAX_STRING_LEN`](crate::error::MAX_STRING_LEN)
+/// bytes.
+fn owned_c_string(ptr: *const c_char) -> CString {
+    unsafe { error::c_str(ptr) }.map_or_else(|_| CString::default(), CString::from)
+}
+
+/// A copy of a [`BootloaderInfoResponse`].
+#[derive(Clone, Debug)]
+pub struct BootloaderInfo {
+    /// The name of the bootloader. Invalid UTF-8 is replaced with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    pub name: String,
+    /// The version of the bootloader. Invalid UTF-8 is replaced with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    pub version: String,
+}
+
+/// A copy of a [`File`](file::File), as returned for the executable and for
+/// modules. The contents of the file are not copied.
+#[derive(Clone)]
+pub struct File {
+    /// The address of the file contents. See [`File::addr`](file::File::addr).
+    pub addr: *mut u8,
+    /// The size of the file, in bytes.
+    pub size: u64,
+    /// The path of the file.
+    pub path: CString,
+    /// The string (command line) associated with the file.
+    pub string: CString,
+    /// The media type of the file.
+    pub media_type: MediaType,
+    /// The IP address of the TFTP server, in network byte order, if the file
+    /// was loaded from TFTP.
+    pub tftp_ip: Option<NonZeroU32>,
+    /// The port of the TFTP server, if the file was loaded from TFTP.
+    pub tftp_port: Option<NonZeroU32>,
+    /// The partition index of the file, if it was loaded from a partition.
+    pub partition_idx: Option<NonZeroU32>,
+    /// The MBR disk ID of the file, if it was loaded from an MBR disk.
+    pub mbr_disk_id: Option<NonZeroU32>,
+    /// The GPT disk UUID of the file, if it was loaded from a GPT disk.
+    pub gpt_disk_id: Option<Uuid>,
+    /// The GPT partition UUID of the file, if it was loaded from a GPT
+    /// partition.
+    pub gpt_partition_id: Option<Uuid>,
+    /// The partition UUID of the file, if it was loaded from a partition with
+    /// a UUID.
+    pub partition_uuid: Option<Uuid>,
+}
+unsafe impl Send for File {}
+unsafe impl Sync for File {}
+impl From<&file::File> for File {
+    fn from(file: &file::File) -> Self {
+        Self {
+            addr: file.addr(),
+            size: file.size(),
+            path: owned_c_string(file.path),
+            string: owned_c_string(file.string),
+            media_type: file.media_type(),
+            tftp_ip: file.tftp_ip,
+            tftp_port: file.tftp_port(),
+            partition_idx: file.partition_idx(),
+            mbr_disk_id: file.mbr_disk_id(),
+            gpt_disk_id: file.gpt_disk_id(),
+            gpt_partition_id: file.gpt_partition_id(),
+            partition_uuid: file.partition_uuid(),
+        }
+    }
+}
+
+/// A copy of a [`Framebuffer`](framebuffer::Framebuffer). The pixels are not
+/// copied.
+#[derive(Clone)]
+pub struct Framebuffer {
+    /// The address of the framebuffer. See
+    /// [`Framebuffer::addr`](framebuffer::Framebuffer::addr).
+    pub addr: *mut u8,
+    /// The current mode of the framebuffer.
+    pub mode: VideoMode,
+    /// The raw EDID bytes of the attached display, if any.
+    pub edid: Option<Vec<u8>>,
+    /// The video modes supported on this framebuffer. Only available on
+    /// revision 1 and above.
+    pub modes: Option<Vec<VideoMode>>,
+}
+unsafe impl Send for Framebuffer {}
+unsafe impl Sync for Framebuffer {}
+impl From<&framebuffer::Framebuffer<'_>> for Framebuffer {
+    fn from(fb: &framebuffer::Framebuffer<'_>) -> Self {
+        Self {
+            addr: fb.addr(),
+            mode: VideoMode {
+                pitch: fb.pitch(),
+                width: fb.width(),
+                height: fb.height(),
+                bpp: fb.bpp(),
+                memory_model: fb.memory_model(),
+                red_mask_size: fb.red_mask_size(),
+                red_mask_shift: fb.red_mask_shift(),
+                green_mask_size: fb.green_mask_size(),
+                green_mask_shift: fb.green_mask_shift(),
+                blue_mask_size: fb.blue_mask_size(),
+                blue_mask_shift: fb.blue_mask_shift(),
+            },
+            edid: fb.edid().map(<[u8]>::to_vec),
+            modes: fb.modes().map(|modes| modes.iter().map(|&&m| m).collect()),
+        }
+    }
+}
+
+/// A copy of a [`Cpu`](crate::mp::Cpu). The goto address is not copied, as
+/// it cannot be used once bootloader memory has been reclaimed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cpu {
+    /// The ACPI processor ID.
+    pub id: u64,
+    /// The architecture-specific ID: the local APIC ID on x86_64, the MPIDR on
+    /// aarch64 and the hart ID on riscv64.
+    pub arch_id: u64,
+}
+
+/// A copy of an [`MpResponse`].
+#[derive(Clone, Debug)]
+pub struct Mp {
+    /// The flags set by the bootloader, as their raw bits.
+    pub flags: u64,
+    /// The architecture-specific ID of the boot processor. See
+    /// [`Cpu::arch_id`].
+    pub bsp_arch_id: u64,
+    /// All processors found, including the boot processor.
+    pub cpus: Vec<Cpu>,
+}
+
+#[cfg(not(target_arch = "loongarch64"))]
+impl From<&MpResponse> for Mp {
+    // The widths of these fields differ between architectures.
+    #[allow(clippy::useless_conversion)]
+    fn from(mp: &MpResponse) -> Self {
+        Self {
+            flags: mp.flags().bits().into(),
+            #[cfg(target_arch = "x86_64")]
+            bsp_arch_id: mp.bsp_lapic_id().into(),
+            #[cfg(target_arch = "aarch64")]
+            bsp_arch_id: mp.bsp_mpidr(),
+            #[cfg(target_arch = "riscv64")]
+            bsp_arch_id: mp.bsp_hartid(),
+            cpus: mp
+                .cpus()
+                .iter()
+                .map(|cpu| Cpu {
+                    id: cpu.id.into(),
+                    #[cfg(target_arch = "x86_64")]
+                    arch_id: cpu.lapic_id.into(),
+                    #[cfg(target_arch = "aarch64")]
+                    arch_id: cpu.mpidr,
+                    #[cfg(target_arch = "riscv64")]
+                    arch_id: cpu.hartid,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A copy of an [`EfiMemoryMapResponse`].
+#[derive(Clone, Debug)]
+pub struct EfiMemoryMap {
+    /// The raw bytes of the EFI memory map.
+    pub memmap: Vec<u8>,
+    /// The size of each EFI memory map entry.
+    pub desc_size: u64,
+    /// The version of each EFI memory map entry.
+    pub desc_version: u32,
+}
+impl EfiMemoryMap {
+    /// Returns an iterator over the typed descriptors of the copied map. See
+    /// [`EfiMemoryMapResponse::descriptors`].
+    pub fn descriptors(&self) -> Result<efi::Descriptors<'_>, Error> {
+        efi::Descriptors::new(&self.memmap, self.desc_size as usize)
+    }
+}
+
+/// Owned copies of every answered response. Each field is `None` until the
+/// matching `with_*` method has been called with an answered request.
+#[derive(Clone, Default)]
+pub struct BootInfo {
+    /// See [`BootloaderInfoResponse`].
+    pub bootloader_info: Option<BootloaderInfo>,
+    /// See [`FirmwareTypeResponse`].
+    pub firmware_type: Option<FirmwareType>,
+    /// See [`HhdmResponse::offset`].
+    pub hhdm_offset: Option<u64>,
+    /// See [`FramebufferResponse`].
+    pub framebuffers: Option<Vec<Framebuffer>>,
+    /// See [`PagingModeResponse`].
+    pub paging_mode: Option<paging::Mode>,
+    /// See [`MpResponse`].
+    pub mp: Option<Mp>,
+    /// See [`MemoryMapResponse`].
+    pub memory_map: Option<Vec<memory_map::Entry>>,
+    /// See [`ExecutableFileResponse`].
+    pub executable_file: Option<File>,
+    /// See [`ModuleResponse`].
+    pub modules: Option<Vec<File>>,
+    /// See [`RsdpResponse`].
+    pub rsdp_address: Option<usize>,
+    /// See [`SmbiosResponse::entry_32`].
+    pub smbios_entry_32: Option<NonZeroUsize>,
+    /// See [`SmbiosResponse::entry_64`].
+    pub smbios_entry_64: Option<NonZeroUsize>,
+    /// See [`EfiSystemTableResponse`].
+    pub efi_system_table_address: Option<usize>,
+    /// See [`EfiMemoryMapResponse`].
+    pub efi_memory_map: Option<EfiMemoryMap>,
+    /// See [`DateAtBootResponse`].
+    pub date_at_boot: Option<Duration>,
+    /// See [`ExecutableAddressResponse::physical_base`].
+    pub executable_physical_base: Option<u64>,
+    /// See [`ExecutableAddressResponse::virtual_base`].
+    pub executable_virtual_base: Option<u64>,
+    /// See [`ExecutableCmdlineResponse`].
+    pub executable_cmdline: Option<CString>,
+    /// A copy of the whole device tree blob. See [`DeviceTreeBlobResponse`].
+    pub device_tree_blob: Option<Vec<u8>>,
+}
+unsafe impl Send for BootInfo {}
+unsafe impl Sync for BootInfo {}
+impl BootInfo {
+    /// Create a new snapshot with no responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy a [`BootloaderInfoResponse`], if present.
+    pub fn with_bootloader_info(mut self, response: Option<&BootloaderInfoResponse>) -> Self {
+        self.bootloader_info = response.map(|r| BootloaderInfo {
+            name: owned_string(r.name),
+            version: owned_string(r.version),
+        });
+        self
+    }
+
+    /// Copy a [`FirmwareTypeResponse`], if present.
+    pub fn with_firmware_type(mut self, response: Option<&FirmwareTypeResponse>) -> Self {
+        self.firmware_type = response.map(FirmwareTypeResponse::firmware_type);
+        self
+    }
+
+    /// Copy a [`HhdmResponse`], if present.
+    pub fn with_hhdm(mut self, response: Option<&HhdmResponse>) -> Self {
+        self.hhdm_offset = response.map(HhdmResponse::offset);
+        self
+    }
+
+    /// Copy a [`FramebufferResponse`], including the EDID and mode list of
+    /// every framebuffer, if present.
+    pub fn with_framebuffers(mut self, response: Option<&FramebufferResponse>) -> Self {
+        self.framebuffers = response.map(|r| r.framebuffers().map(|fb| (&fb).into()).collect());
+        self
+    }
+
+    /// Copy a [`PagingModeResponse`], if present.
+    pub fn with_paging_mode(mut self, response: Option<&PagingModeResponse>) -> Self {
+        self.paging_mode = response.map(PagingModeResponse::mode);
+        self
+    }
+
+    /// Copy an [`MpResponse`], if present. This is a no-op on loongarch64,
+    /// where the response carries no information.
+    pub fn with_mp(mut self, response: Option<&MpResponse>) -> Self {
+        #[cfg(not(target_arch = "loongarch64"))]
+        {
+            self.mp = response.map(Mp::from);
+        }
+        #[cfg(target_arch = "loongarch64")]
+        let _ = response;
+        self
+    }
+
+    /// Copy a [`MemoryMapResponse`], if present.
+    pub fn with_memory_map(mut self, response: Option<&MemoryMapResponse>) -> Self {
+        self.memory_map = response.map(|r| r.entries().iter().map(|&&e| e).collect());
+        self
+    }
+
+    /// Copy an [`ExecutableFileResponse`], if present.
+    pub fn with_executable_file(mut self, response: Option<&ExecutableFileResponse>) -> Self {
+        self.executable_file = response.map(|r| r.file().into());
+        self
+    }
+
+    /// Copy a [`ModuleResponse`], if present.
+    pub fn with_modules(mut self, response: Option<&ModuleResponse>) -> Self {
+        self.modules = response.map(|r| r.modules().iter().map(|&m| m.into()).collect());
+        self
+    }
+
+    /// Copy an [`RsdpResponse`], if present.
+    pub fn with_rsdp(mut self, response: Option<&RsdpResponse>) -> Self {
+        self.rsdp_address = response.map(RsdpResponse::address);
+        self
+    }
+
+    /// Copy a [`SmbiosResponse`], if present.
+    pub fn with_smbios(mut self, response: Option<&SmbiosResponse>) -> Self {
+        self.smbios_entry_32 = response.and_then(SmbiosResponse::entry_32);
+        self.smbios_entry_64 = response.and_then(SmbiosResponse::entry_64);
+        self
+    }
+
+    /// Copy an [`EfiSystemTableResponse`], if present.
+    pub fn with_efi_system_table(mut self, response: Option<&EfiSystemTableResponse>) -> Self {
+        self.efi_system_table_address = response.map(EfiSystemTableResponse::address);
+        self
+    }
+
+    /// Copy an [`EfiMemoryMapResponse`], including the memory map itself, if
+    /// present.
+    pub fn with_efi_memory_map(mut self, response: Option<&EfiMemoryMapResponse>) -> Self {
+        self.efi_memory_map = response.map(|r| EfiMemoryMap {
+            memmap: unsafe {
+                core::slice::from_raw_parts(r.memmap().cast::<u8>(), r.memmap_size() as usize)
+            }
+            .to_vec(),
+            desc_size: r.desc_size(),
+            desc_version: r.desc_version(),
+        });
+        self
+    }
+
+    /// Copy a [`DateAtBootResponse`], if present.
+    pub fn with_date_at_boot(mut self, response: Option<&DateAtBootResponse>) -> Self {
+        self.date_at_boot = response.map(DateAtBootResponse::timestamp);
+        self
+    }
+
+    /// Copy an [`ExecutableAddressResponse`], if present.
+    pub fn with_executable_address(mut self, response: Option<&ExecutableAddressResponse>) -> Self {
+        self.executable_physical_base = response.map(ExecutableAddressResponse::physical_base);
+        self.executable_virtual_base = response.map(ExecutableAddressResponse::virtual_base);
+        self
+    }
+
+    /// Copy an [`ExecutableCmdlineResponse`], if present.
+    pub fn with_executable_cmdline(mut self, response: Option<&ExecutableCmdlineResponse>) -> Self {
+        self.executable_cmdline = response.map(|r| owned_c_string(r.cmdline));
+        self
+    }
+
+    /// Copy a [`DeviceTreeBlobResponse`], including the whole blob, if
+    /// present. The size of the blob is taken from its header, which is
+    /// validated first with [`DeviceTreeBlobResponse::fdt`]; a blob with an
+    /// invalid header is skipped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "mock")] {
+    /// use limine::{boot_info::BootInfo, mock};
+    ///
+    /// let blob = include_bytes!("fdt/example.dtb");
+    /// let info = BootInfo::new().with_device_tree_blob(Some(mock::device_tree_blob(blob)));
+    /// assert_eq!(info.device_tree_blob.as_deref(), Some(&blob[..]));
+    ///
+    /// let info = BootInfo::new().with_device_tree_blob(Some(mock::device_tree_blob(b"not a dtb")));
+    /// assert_eq!(info.device_tree_blob, None);
+    /// # }
+    /// ```
+    pub fn with_device_tree_blob(mut self, response: Option<&DeviceTreeBlobResponse>) -> Self {
+        self.device_tree_blob = response
+            .and_then(|r| r.fdt().ok())
+            .map(|fdt| fdt.as_bytes().to_vec());
+        self
+    }
+}
@@ -0,0 +1,340 @@
+//! Parsing of kernel and module command lines, such as the one returned by
+//! [`ExecutableCmdlineResponse::cmdline`](crate::response::ExecutableCmdlineResponse::cmdline),
+//! without allocating.
+//!
+//! A command line is split into arguments on whitespace. Whitespace inside
+//! double or single quotes, or escaped with a backslash, does not split
+//! arguments. Each argument is either a bare flag, such as `nosmp`, or a
+//! `key=value` pair split on the first unquoted `=`. Keys may be repeated;
+//! single-value lookups return the last occurrence.
+//!
+//! # Examples
+//! ```rust
+//! use limine::cmdline::Cmdline;
+//!
+//! let cmdline = Cmdline::new(r#"loglevel=7 nosmp mem=64M console="ttyS0 115200" init=/bin/s\ h"#);
+//! assert_eq!(cmdline.get::<u64>("loglevel"), Some(7));
+//! assert_eq!(cmdline.get_bool("nosmp"), Some(true));
+//! assert_eq!(cmdline.get_size("mem"), Some(64 << 20));
+//! assert_eq!(cmdline.get_str("console"), Some("ttyS0 115200"));
+//! assert!(cmdline.value("init").unwrap() == "/bin/s h");
+//! assert_eq!(cmdline.args().count(), 5);
+//! ```
+
+use core::{ffi::CStr, fmt, str::FromStr};
+
+use crate::Error;
+
+/// The longest escaped value, in bytes, that [`Cmdline::get`] will unescape.
+/// Longer values can be unescaped with [`Value::unescape`].
+const MAX_UNESCAPED_LEN: usize = 128;
+
+/// A command line.
+#[derive(Clone, Copy)]
+pub struct Cmdline<'a> {
+    s: &'a str,
+}
+impl<'a> Cmdline<'a> {
+    /// Wrap a command line.
+    pub const fn new(s: &'a str) -> Self {
+        Self { s }
+    }
+
+    /// Wrap a command line returned by the bootloader, such as
+    /// [`File::string`](crate::file::File::string), or return an error if it
+    /// is not valid UTF-8.
+    pub fn from_c_str(s: &'a CStr) -> Result<Self, Error> {
+        Ok(Self::new(s.to_str()?))
+    }
+
+    /// The whole command line.
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    /// Returns the arguments of the command line, in order.
+    pub fn args(&self) -> Args<'a> {
+        Args { rest: self.s }
+    }
+
+    /// Returns whether an argument with the given key is present, either as a
+    /// flag or with a value.
+    pub fn contains(&self, key: &str) -> bool {
+        self.args().any(|arg| arg.key() == key)
+    }
+
+    /// Returns every value given for a key, in order. Occurrences of the key as
+    /// a bare flag are skipped.
+    pub fn values<'k>(&self, key: &'k str) -> impl Iterator<Item = Value<'a>> + 'k
+    where
+        'a: 'k,
+    {
+        self.args()
+            .filter(move |arg| arg.key() == key)
+            .filter_map(|arg| arg.value())
+    }
+
+    /// Returns the last value given for a key.
+    pub fn value(&self, key: &str) -> Option<Value<'a>> {
+        self.values(key).last()
+    }
+
+    /// Returns the last value given for a key, if it does not need
+    /// unescaping. See [`Value::as_str`].
+    pub fn get_str(&self, key: &str) -> Option<&'a str> {
+        self.value(key)?.as_str()
+    }
+
+    /// Parse the last value given for a key. Returns `None` if the key has no
+    /// value, or if the value does not parse.
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.value(key)?.parse()
+    }
+
+    /// Parse the last occurrence of a key as a boolean. A bare flag is `true`;
+    /// otherwise the value must be one of `1`, `y`, `yes`, `on` or `true`, or
+    /// `0`, `n`, `no`, `off` or `false`, ignoring case.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        let arg = self.args().filter(|arg| arg.key() == key).last()?;
+        match arg.value() {
+            Some(value) => value.with_str(parse_bool)?,
+            None => Some(true),
+        }
+    }
+
+    /// Parse the last value given for a key as a size. See [`parse_size`].
+    pub fn get_size(&self, key: &str) -> Option<u64> {
+        self.value(key)?.with_str(parse_size)?
+    }
+}
+impl fmt::Display for Cmdline<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.s)
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    const TRUE: [&str; 5] = ["1", "y", "yes", "on", "true"];
+    const FALSE: [&str; 5] = ["0", "n", "no", "off", "false"];
+    if TRUE.iter().any(|t| s.eq_ignore_ascii_case(t)) {
+        Some(true)
+    } else if FALSE.iter().any(|f| s.eq_ignore_ascii_case(f)) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Parse a size, such as `4096`, `0x1000`, `64M` or `2g`. The optional
+/// suffix is one of `K`, `M`, `G`, `T`, `P` or `E`, ignoring case, and
+/// multiplies the number by the matching power of 1024. Since `e` is also a
+/// hex digit, hex numbers cannot use the `E` suffix. Returns `None` on
+/// overflow.
+///
+/// # Examples
+/// ```rust
+/// use limine::cmdline::parse_size;
+///
+/// assert_eq!(parse_size("512"), Some(512));
+/// assert_eq!(parse_size("0x10k"), Some(0x4000));
+/// assert_eq!(parse_size("2G"), Some(2 << 30));
+/// assert_eq!(parse_size("16E"), None);
+/// assert_eq!(parse_size("0x1e"), Some(0x1e));
+/// assert_eq!(parse_size("0xfe"), Some(0xfe));
+/// ```
+pub fn parse_size(s: &str) -> Option<u64> {
+    let hex = s.starts_with("0x") || s.starts_with("0X");
+    let (digits, shift) = match s.as_bytes().last()?.to_ascii_lowercase() {
+        b'k' => (&s[..s.len() - 1], 10),
+        b'm' => (&s[..s.len() - 1], 20),
+        b'g' => (&s[..s.len() - 1], 30),
+        b't' => (&s[..s.len() - 1], 40),
+        b'p' => (&s[..s.len() - 1], 50),
+        b'e' if !hex => (&s[..s.len() - 1], 60),
+        _ => (s, 0),
+    };
+    let number = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    number.checked_mul(1 << shift)
+}
+
+/// Walks a raw argument, removing quotes and escapes. Yields the byte offset
+/// of each remaining character, the character, and whether it was quoted or
+/// escaped.
+struct Unquote<'a> {
+    chars: core::str::CharIndices<'a>,
+    quote: Option<char>,
+    escaped: bool,
+}
+impl<'a> Unquote<'a> {
+    fn new(raw: &'a str) -> Self {
+        Self {
+            chars: raw.char_indices(),
+            quote: None,
+            escaped: false,
+        }
+    }
+}
+impl Iterator for Unquote<'_> {
+    type Item = (usize, char, bool);
+
+    fn next(&mut self) -> Option<(usize, char, bool)> {
+        loop {
+            let (i, c) = self.chars.next()?;
+            if self.escaped {
+                self.escaped = false;
+                return Some((i, c, true));
+            } else if self.quote == Some(c) {
+                self.quote = None;
+            } else if self.quote == Some('\'') {
+                return Some((i, c, true));
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if self.quote.is_none() && (c == '"' || c == '\'') {
+                self.quote = Some(c);
+            } else {
+                return Some((i, c, self.quote.is_some()));
+            }
+        }
+    }
+}
+
+/// An iterator over the arguments of a command line, as returned by
+/// [`Cmdline::args`].
+#[derive(Clone)]
+pub struct Args<'a> {
+    rest: &'a str,
+}
+impl<'a> Iterator for Args<'a> {
+    type Item = Arg<'a>;
+
+    fn next(&mut self) -> Option<Arg<'a>> {
+        self.rest = self.rest.trim_start();
+        if self.rest.is_empty() {
+            return None;
+        }
+        let mut eq = None;
+        let end = Unquote::new(self.rest)
+            .find(|&(i, c, literal)| {
+                if !literal && c == '=' && eq.is_none() {
+                    eq = Some(i);
+                }
+                !literal && c.is_whitespace()
+            })
+            .map_or(self.rest.len(), |(i, _, _)| i);
+        let (raw, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(match eq {
+            Some(eq) => Arg {
+                key: Value { raw: &raw[..eq] },
+                value: Some(Value {
+                    raw: &raw[eq + 1..],
+                }),
+            },
+            None => Arg {
+                key: Value { raw },
+                value: None,
+            },
+        })
+    }
+}
+
+/// An argument of a command line: a bare flag, or a `key=value` pair.
+#[derive(Clone, Copy)]
+pub struct Arg<'a> {
+    key: Value<'a>,
+    value: Option<Value<'a>>,
+}
+impl<'a> Arg<'a> {
+    /// The key of the argument, or the whole argument if it is a bare flag.
+    pub fn key(&self) -> Value<'a> {
+        self.key
+    }
+
+    /// The value of the argument, or `None` if it is a bare flag.
+    pub fn value(&self) -> Option<Value<'a>> {
+        self.value
+    }
+}
+
+/// A key or value from a command line, which may contain quotes and escapes.
+#[derive(Clone, Copy)]
+pub struct Value<'a> {
+    raw: &'a str,
+}
+impl<'a> Value<'a> {
+    /// The text as written in the command line, including any quotes and
+    /// escapes.
+    pub fn raw(&self) -> &'a str {
+        self.raw
+    }
+
+    /// Returns the text as a string slice, if it can be borrowed from the
+    /// command line without unescaping: either it has no quotes or escapes,
+    /// or it is entirely enclosed in one pair of quotes.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let plain = |s: &str| !s.contains(['"', '\'', '\\']);
+        if plain(self.raw) {
+            return Some(self.raw);
+        }
+        ['"', '\'']
+            .into_iter()
+            .find_map(|q| self.raw.strip_prefix(q)?.strip_suffix(q))
+            .filter(|inner| plain(inner))
+    }
+
+    /// Returns the characters of the text, with quotes and escapes removed.
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        Unquote::new(self.raw).map(|(_, c, _)| c)
+    }
+
+    /// Unescape the text into `buf`, returning the unescaped string.
+    pub fn unescape<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str, Error> {
+        let needed = self.chars().map(char::len_utf8).sum();
+        if buf.len() < needed {
+            return Err(Error::BufferTooSmall { needed });
+        }
+        let mut len = 0;
+        for c in self.chars() {
+            len += c.encode_utf8(&mut buf[len..]).len();
+        }
+        Ok(core::str::from_utf8(&buf[..len]).unwrap())
+    }
+
+    /// Parse the unescaped text. Returns `None` if it does not parse, or if it
+    /// is longer than 128 bytes once unescaped.
+    pub fn parse<T: FromStr>(&self) -> Option<T> {
+        self.with_str(|s| s.parse().ok())?
+    }
+
+    /// Call `f` with the unescaped text, unescaping it on the stack if needed.
+    fn with_str<T>(&self, f: impl FnOnce(&str) -> T) -> Option<T> {
+        match self.as_str() {
+            Some(s) => Some(f(s)),
+            None => {
+                let mut buf = [0; MAX_UNESCAPED_LEN];
+                self.unescape(&mut buf).ok().map(f)
+            }
+        }
+    }
+}
+impl PartialEq<str> for Value<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+impl PartialEq<&str> for Value<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars().try_for_each(|c| fmt::Write::write_char(f, c))
+    }
+}
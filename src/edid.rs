@@ -0,0 +1,271 @@
+//! A parser for the EDID (Extended Display Identification Data) returned by
+//! [`Framebuffer::edid`](crate::framebuffer::Framebuffer::edid).
+//!
+//! Only the 128-byte base block of EDID 1.x is decoded. Extension blocks, such
+//! as CTA-861, are exposed as raw bytes.
+
+use crate::{phys::checksum, Error};
+
+/// The size of an EDID block, in bytes.
+pub const BLOCK_SIZE: usize = 128;
+
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+/// A detailed timing descriptor, describing a video mode exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetailedTiming {
+    /// The pixel clock, in kHz.
+    pub pixel_clock_khz: u32,
+    /// The number of visible pixels per line.
+    pub width: u16,
+    /// The number of visible lines.
+    pub height: u16,
+    /// The number of blanking pixels per line.
+    pub horizontal_blanking: u16,
+    /// The number of blanking lines.
+    pub vertical_blanking: u16,
+    /// The number of pixels from the end of the visible area to the start of
+    /// the horizontal sync pulse.
+    pub horizontal_sync_offset: u16,
+    /// The width of the horizontal sync pulse, in pixels.
+    pub horizontal_sync_width: u16,
+    /// The number of lines from the end of the visible area to the start of
+    /// the vertical sync pulse.
+    pub vertical_sync_offset: u8,
+    /// The width of the vertical sync pulse, in lines.
+    pub vertical_sync_width: u8,
+    /// The width of the visible image, in millimetres.
+    pub width_mm: u16,
+    /// The height of the visible image, in millimetres.
+    pub height_mm: u16,
+    /// Whether the mode is interlaced.
+    pub interlaced: bool,
+}
+impl DetailedTiming {
+    fn parse(d: &[u8]) -> Option<Self> {
+        let pixel_clock = u16::from_le_bytes([d[0], d[1]]);
+        if pixel_clock == 0 {
+            return None;
+        }
+        let high = |byte: u8, shift: u8| ((byte >> shift) as u16 & 0xf) << 8;
+        Some(Self {
+            pixel_clock_khz: pixel_clock as u32 * 10,
+            width: d[2] as u16 | high(d[4], 4),
+            horizontal_blanking: d[3] as u16 | high(d[4], 0),
+            height: d[5] as u16 | high(d[7], 4),
+            vertical_blanking: d[6] as u16 | high(d[7], 0),
+            horizontal_sync_offset: d[8] as u16 | ((d[11] as u16 >> 6) & 0x3) << 8,
+            horizontal_sync_width: d[9] as u16 | ((d[11] as u16 >> 4) & 0x3) << 8,
+            vertical_sync_offset: (d[10] >> 4) | ((d[11] >> 2) & 0x3) << 4,
+            vertical_sync_width: (d[10] & 0xf) | (d[11] & 0x3) << 4,
+            width_mm: d[12] as u16 | high(d[14], 4),
+            height_mm: d[13] as u16 | high(d[14], 0),
+            interlaced: d[17] & 0x80 != 0,
+        })
+    }
+
+    /// The refresh rate, in millihertz.
+    pub fn refresh_rate_mhz(&self) -> u32 {
+        let total = (self.width as u64 + self.horizontal_blanking as u64)
+            * (self.height as u64 + self.vertical_blanking as u64);
+        match total {
+            0 => 0,
+            total => (self.pixel_clock_khz as u64 * 1_000_000 / total) as u32,
+        }
+    }
+}
+
+/// A standard timing, describing a video mode by resolution and refresh rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandardTiming {
+    /// The number of visible pixels per line.
+    pub width: u16,
+    /// The number of visible lines.
+    pub height: u16,
+    /// The refresh rate, in hertz.
+    pub refresh_rate: u8,
+}
+impl StandardTiming {
+    fn parse(version: (u8, u8), bytes: [u8; 2]) -> Option<Self> {
+        if bytes == [0x01, 0x01] || bytes[0] == 0 {
+            return None;
+        }
+        let width = (bytes[0] as u16 + 31) * 8;
+        let height = match bytes[1] >> 6 {
+            // 16:10, except before EDID 1.3 where this meant 1:1
+            0 if version < (1, 3) => width,
+            0 => width * 10 / 16,
+            1 => width * 3 / 4,
+            2 => width * 4 / 5,
+            _ => width * 9 / 16,
+        };
+        Some(Self {
+            width,
+            height,
+            refresh_rate: (bytes[1] & 0x3f) + 60,
+        })
+    }
+}
+
+/// A validated EDID.
+///
+/// # Examples
+/// ```rust
+/// use limine::{edid::Edid, Error};
+///
+/// let mut bytes = [0u8; 128];
+/// bytes[..8].copy_from_slice(&[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+/// bytes[8..10].copy_from_slice(&[0x10, 0xac]); // "DEL"
+/// bytes[10..12].copy_from_slice(&0xa0b1u16.to_le_bytes());
+/// bytes[18..24].copy_from_slice(&[1, 4, 0x80, 60, 34, 0x78]);
+/// bytes[38..54].fill(0x01);
+/// bytes[38..40].copy_from_slice(&[0x81, 0x80]); // 1280x1024 at 60 Hz
+/// bytes[54..72].copy_from_slice(&[
+///     0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40, 0x58, 0x2c, //
+///     0x45, 0x00, 0x13, 0x2b, 0x21, 0x00, 0x00, 0x1e,
+/// ]); // 1920x1080 at 60 Hz
+/// bytes[72..90].copy_from_slice(b"\0\0\0\xfc\0Monitor\n     ");
+/// bytes[127] = 0u8.wrapping_sub(bytes.iter().fold(0u8, |a, &b| a.wrapping_add(b)));
+///
+/// let edid = Edid::parse(&bytes).unwrap();
+/// assert_eq!(&edid.manufacturer_id(), b"DEL");
+/// assert_eq!(edid.product_code(), 0xa0b1);
+/// assert_eq!(edid.physical_size_cm(), Some((60, 34)));
+/// assert_eq!(edid.name(), Some("Monitor"));
+///
+/// let preferred = edid.preferred_timing().unwrap();
+/// assert_eq!((preferred.width, preferred.height), (1920, 1080));
+/// assert_eq!(preferred.refresh_rate_mhz() / 1000, 60);
+///
+/// let standard = edid.standard_timings().next().unwrap();
+/// assert_eq!((standard.width, standard.height), (1280, 1024));
+///
+/// bytes[127] ^= 1;
+/// assert_eq!(Edid::parse(&bytes).err(), Some(Error::InvalidChecksum));
+/// ```
+#[derive(Clone, Copy)]
+pub struct Edid<'a> {
+    base: &'a [u8; BLOCK_SIZE],
+    extensions: &'a [u8],
+}
+impl<'a> Edid<'a> {
+    /// Validate the header and checksum of an EDID.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (base, rest) = bytes
+            .split_first_chunk::<BLOCK_SIZE>()
+            .ok_or(Error::Truncated)?;
+        if base[..8] != HEADER {
+            return Err(Error::InvalidSignature);
+        }
+        if !checksum(base) {
+            return Err(Error::InvalidChecksum);
+        }
+        let extensions = base[126] as usize * BLOCK_SIZE;
+        Ok(Self {
+            base,
+            extensions: &rest[..extensions.min(rest.len() / BLOCK_SIZE * BLOCK_SIZE)],
+        })
+    }
+
+    /// The raw bytes of the base block.
+    pub fn bytes(&self) -> &'a [u8; BLOCK_SIZE] {
+        self.base
+    }
+
+    /// The three-letter PNP ID of the manufacturer, such as `b"DEL"`.
+    pub fn manufacturer_id(&self) -> [u8; 3] {
+        let id = u16::from_be_bytes([self.base[8], self.base[9]]);
+        [10, 5, 0].map(|shift| b'@' + ((id >> shift) & 0x1f) as u8)
+    }
+
+    /// The manufacturer's product code.
+    pub fn product_code(&self) -> u16 {
+        u16::from_le_bytes([self.base[10], self.base[11]])
+    }
+
+    /// The serial number, or 0 if it is not given. Many displays store their
+    /// serial as a string instead; see [`serial_string`](Self::serial_string).
+    pub fn serial(&self) -> u32 {
+        u32::from_le_bytes([self.base[12], self.base[13], self.base[14], self.base[15]])
+    }
+
+    /// The week of manufacture, from 1 to 54, or `None` if it is not given or
+    /// the year is a model year.
+    pub fn manufacture_week(&self) -> Option<u8> {
+        matches!(self.base[16], 1..=54).then_some(self.base[16])
+    }
+
+    /// The year of manufacture, or the model year.
+    pub fn manufacture_year(&self) -> u16 {
+        self.base[17] as u16 + 1990
+    }
+
+    /// The EDID version, as `(version, revision)`.
+    pub fn version(&self) -> (u8, u8) {
+        (self.base[18], self.base[19])
+    }
+
+    /// The physical size of the display, as `(width, height)` in centimetres,
+    /// or `None` if it is unknown or only an aspect ratio is given.
+    pub fn physical_size_cm(&self) -> Option<(u8, u8)> {
+        let (width, height) = (self.base[21], self.base[22]);
+        (width != 0 && height != 0).then_some((width, height))
+    }
+
+    /// The number of extension blocks following the base block. These are not
+    /// necessarily all present in the bytes given to [`parse`](Self::parse).
+    pub fn extension_count(&self) -> u8 {
+        self.base[126]
+    }
+
+    /// The extension blocks present in the bytes given to
+    /// [`parse`](Self::parse).
+    pub fn extensions(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.extensions.chunks_exact(BLOCK_SIZE)
+    }
+
+    /// The standard timings supported by the display.
+    pub fn standard_timings(&self) -> impl Iterator<Item = StandardTiming> + 'a {
+        let version = self.version();
+        self.base[38..54]
+            .chunks_exact(2)
+            .filter_map(move |t| StandardTiming::parse(version, [t[0], t[1]]))
+    }
+
+    fn descriptors(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.base[54..126].chunks_exact(18)
+    }
+
+    /// The detailed timings supported by the display.
+    pub fn detailed_timings(&self) -> impl Iterator<Item = DetailedTiming> + 'a {
+        self.descriptors().filter_map(DetailedTiming::parse)
+    }
+
+    /// The preferred timing of the display, which is usually its native
+    /// resolution. This is the first detailed timing.
+    pub fn preferred_timing(&self) -> Option<DetailedTiming> {
+        self.descriptors().next().and_then(DetailedTiming::parse)
+    }
+
+    /// Returns the text of the first display descriptor with the given tag.
+    fn descriptor_string(&self, tag: u8) -> Option<&'a str> {
+        let d = self
+            .descriptors()
+            .find(|d| d[..3] == [0, 0, 0] && d[3] == tag)?;
+        let text = &d[5..];
+        let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+        core::str::from_utf8(&text[..end])
+            .ok()
+            .map(|s| s.trim_end())
+    }
+
+    /// The name of the display, if given.
+    pub fn name(&self) -> Option<&'a str> {
+        self.descriptor_string(0xfc)
+    }
+
+    /// The serial number of the display as a string, if given.
+    pub fn serial_string(&self) -> Option<&'a str> {
+        self.descriptor_string(0xff)
+    }
+}
@@ -0,0 +1,742 @@
+//! Types for reading the UEFI structures passed on by the bootloader, such as
+//! the [EFI memory map](crate::response::EfiMemoryMapResponse) and the [EFI
+//! system table](crate::response::EfiSystemTableResponse).
+
+use core::{ffi::c_void, fmt, marker::PhantomData, mem::size_of};
+
+use bitflags::bitflags;
+
+use crate::{
+    addr::PhysAddr,
+    error::MAX_STRING_LEN,
+    file::Uuid,
+    memory_map::{Entry, EntryType},
+    phys::{response_address, translate},
+    response::{EfiSystemTableResponse, HhdmResponse},
+    Error,
+};
+
+/// The size of a UEFI page, in bytes. This is always 4 KiB, regardless of the
+/// page size used by the platform.
+pub const PAGE_SIZE: u64 = 0x1000;
+
+/// The type of a region in the EFI memory map.
+#[repr(transparent)]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct MemoryType(u32);
+impl MemoryType {
+    /// Not usable.
+    pub const RESERVED: Self = Self(0);
+    /// The code of UEFI applications, such as the bootloader.
+    pub const LOADER_CODE: Self = Self(1);
+    /// Memory allocated by UEFI applications, such as the bootloader.
+    pub const LOADER_DATA: Self = Self(2);
+    /// The code of boot services drivers.
+    pub const BOOT_SERVICES_CODE: Self = Self(3);
+    /// Memory allocated by boot services drivers.
+    pub const BOOT_SERVICES_DATA: Self = Self(4);
+    /// The code of runtime services drivers, which must be preserved.
+    pub const RUNTIME_SERVICES_CODE: Self = Self(5);
+    /// Memory allocated by runtime services drivers, which must be preserved.
+    pub const RUNTIME_SERVICES_DATA: Self = Self(6);
+    /// Free memory.
+    pub const CONVENTIONAL: Self = Self(7);
+    /// Memory with detected errors.
+    pub const UNUSABLE: Self = Self(8);
+    /// ACPI tables, which can be reclaimed once they have been read.
+    pub const ACPI_RECLAIM: Self = Self(9);
+    /// Memory reserved by the firmware for ACPI.
+    pub const ACPI_NVS: Self = Self(10);
+    /// Memory-mapped I/O used by runtime services.
+    pub const MMIO: Self = Self(11);
+    /// Memory-mapped I/O port space used by runtime services.
+    pub const MMIO_PORT_SPACE: Self = Self(12);
+    /// Code used by the processor's firmware.
+    pub const PAL_CODE: Self = Self(13);
+    /// Free persistent memory.
+    pub const PERSISTENT: Self = Self(14);
+    /// Free memory which must be accepted before it can be used.
+    pub const UNACCEPTED: Self = Self(15);
+
+    /// Returns whether memory of this type is free for the OS to use once boot
+    /// services have exited, which Limine always does before entering the
+    /// executable.
+    pub fn is_available(self) -> bool {
+        matches!(
+            self,
+            Self::LOADER_CODE
+                | Self::LOADER_DATA
+                | Self::BOOT_SERVICES_CODE
+                | Self::BOOT_SERVICES_DATA
+                | Self::CONVENTIONAL
+        )
+    }
+}
+
+bitflags! {
+    /// The attributes of a region in the EFI memory map.
+    #[derive(Default, Clone, Copy, PartialEq, Eq)]
+    pub struct MemoryAttribute: u64 {
+        /// The region can be mapped uncacheable.
+        const UC = 1 << 0;
+        /// The region can be mapped write-combining.
+        const WC = 1 << 1;
+        /// The region can be mapped write-through.
+        const WT = 1 << 2;
+        /// The region can be mapped write-back.
+        const WB = 1 << 3;
+        /// The region can be mapped uncacheable, exported, and supports the
+        /// "fetch and add" semaphore mechanism.
+        const UCE = 1 << 4;
+        /// The region can be write-protected.
+        const WP = 1 << 12;
+        /// The region can be read-protected.
+        const RP = 1 << 13;
+        /// The region can be execute-protected.
+        const XP = 1 << 14;
+        /// The region is non-volatile.
+        const NV = 1 << 15;
+        /// The region is more reliable than other memory.
+        const MORE_RELIABLE = 1 << 16;
+        /// The region can be made read-only.
+        const RO = 1 << 17;
+        /// The region is specific-purpose memory.
+        const SP = 1 << 18;
+        /// The region can be protected with the CPU's memory cryptography.
+        const CPU_CRYPTO = 1 << 19;
+        /// The region must be mapped by the OS for runtime services.
+        const RUNTIME = 1 << 63;
+
+        const _ = !0;
+    }
+}
+
+/// A region in the EFI memory map.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MemoryDescriptor {
+    /// The type of the region.
+    pub ty: MemoryType,
+    /// The physical address of the start of the region.
+    pub phys_start: u64,
+    /// The virtual address of the start of the region. This is only
+    /// meaningful for runtime regions once a virtual address map is set.
+    pub virt_start: u64,
+    /// The size of the region, in 4 KiB [pages](PAGE_SIZE).
+    pub page_count: u64,
+    /// The attributes of the region.
+    pub attribute: MemoryAttribute,
+}
+impl MemoryDescriptor {
+    /// The end of the region (exclusive), in *physical space*. Saturates at
+    /// `u64::MAX` if the descriptor is malformed.
+    pub fn phys_end(&self) -> u64 {
+        self.phys_start
+            .saturating_add(self.page_count.saturating_mul(PAGE_SIZE))
+    }
+}
+
+/// An iterator over the descriptors of an EFI memory map, as returned by
+/// [`EfiMemoryMapResponse::descriptors`](crate::response::EfiMemoryMapResponse::descriptors).
+///
+/// Firmware may use descriptors larger than [`MemoryDescriptor`], so the
+/// map is walked using its own descriptor size.
+///
+/// # Examples
+/// ```rust
+/// use limine::efi::{Descriptors, MemoryAttribute, MemoryType};
+///
+/// // Two 48-byte descriptors, padded past the 40 bytes defined by UEFI
+/// let mut map = [0u8; 96];
+/// for (desc, (ty, start, pages)) in map.chunks_mut(48).zip([(7u32, 0x1000u64, 3u64), (5, 0x8000, 1)]) {
+///     desc[0..4].copy_from_slice(&ty.to_le_bytes());
+///     desc[8..16].copy_from_slice(&start.to_le_bytes());
+///     desc[24..32].copy_from_slice(&pages.to_le_bytes());
+///     desc[32..40].copy_from_slice(&MemoryAttribute::WB.bits().to_le_bytes());
+/// }
+///
+/// let mut descriptors = Descriptors::new(&map, 48).unwrap();
+/// let first = descriptors.next().unwrap();
+/// assert!(first.ty == MemoryType::CONVENTIONAL);
+/// assert_eq!(first.phys_end(), 0x4000);
+/// assert!(first.attribute.contains(MemoryAttribute::WB));
+/// assert!(descriptors.next().unwrap().ty == MemoryType::RUNTIME_SERVICES_CODE);
+/// assert!(descriptors.next().is_none());
+/// ```
+#[derive(Clone)]
+pub struct Descriptors<'a> {
+    chunks: core::slice::ChunksExact<'a, u8>,
+}
+impl<'a> Descriptors<'a> {
+    /// Iterate over a memory map stored in `bytes`, made of descriptors of
+    /// `desc_size` bytes each. Returns an error if `desc_size` is smaller than
+    /// a [`MemoryDescriptor`].
+    pub fn new(bytes: &'a [u8], desc_size: usize) -> Result<Self, Error> {
+        if desc_size < size_of::<MemoryDescriptor>() {
+            return Err(Error::InvalidDescriptorSize { size: desc_size });
+        }
+        Ok(Self {
+            chunks: bytes.chunks_exact(desc_size),
+        })
+    }
+}
+impl Iterator for Descriptors<'_> {
+    type Item = MemoryDescriptor;
+
+    fn next(&mut self) -> Option<MemoryDescriptor> {
+        let chunk = self.chunks.next()?;
+        Some(unsafe { chunk.as_ptr().cast::<MemoryDescriptor>().read_unaligned() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+impl ExactSizeIterator for Descriptors<'_> {}
+
+/// A disagreement between the EFI memory map and the Limine [memory
+/// map](crate::memory_map), as returned by [`conflicts`].
+#[derive(Clone, Copy)]
+pub struct Conflict {
+    /// The Limine memory map entry.
+    pub entry: Entry,
+    /// The EFI memory descriptor overlapping it.
+    pub descriptor: MemoryDescriptor,
+}
+
+/// Cross-check the EFI memory map against the Limine memory map, returning
+/// every [`USABLE`](EntryType::USABLE) Limine entry which overlaps an EFI
+/// region that the OS may not use (see [`MemoryType::is_available`]).
+///
+/// A well-behaved bootloader never produces a conflict, so any result points
+/// at a firmware or bootloader bug, such as runtime services memory being
+/// handed out as free.
+///
+/// # Examples
+/// ```rust
+/// use limine::{
+///     efi::{self, MemoryAttribute, MemoryDescriptor, MemoryType},
+///     memory_map::{Entry, EntryType},
+/// };
+///
+/// let runtime = MemoryDescriptor {
+///     ty: MemoryType::RUNTIME_SERVICES_DATA,
+///     phys_start: 0x8000,
+///     virt_start: 0,
+///     page_count: 2,
+///     attribute: MemoryAttribute::RUNTIME,
+/// };
+/// let usable = Entry { base: 0x0, length: 0x9000, entry_type: EntryType::USABLE };
+///
+/// let conflict = efi::conflicts([runtime], &[&usable]).next().unwrap();
+/// assert_eq!(conflict.descriptor.phys_start, 0x8000);
+/// ```
+pub fn conflicts<'a, I>(
+    descriptors: I,
+    entries: &'a [&'a Entry],
+) -> impl Iterator<Item = Conflict> + 'a
+where
+    I: IntoIterator<Item = MemoryDescriptor>,
+    I::IntoIter: 'a,
+{
+    descriptors
+        .into_iter()
+        .filter(|descriptor| !descriptor.ty.is_available())
+        .flat_map(move |descriptor| {
+            entries
+                .iter()
+                .filter(move |entry| {
+                    entry.entry_type == EntryType::USABLE
+                        && entry.base < descriptor.phys_end()
+                        && descriptor.phys_start < entry.end()
+                })
+                .map(move |&&entry| Conflict { entry, descriptor })
+        })
+}
+
+/// A GUID, as used by UEFI to identify configuration tables and variables.
+pub type Guid = Uuid;
+
+/// The GUID of the ACPI 2.0 RSDP configuration table.
+pub const ACPI_20_TABLE_GUID: Guid = Guid {
+    a: 0x8868e871,
+    b: 0xe4f1,
+    c: 0x11d3,
+    d: [0xbc, 0x22, 0x00, 0x80, 0xc7, 0x3c, 0x88, 0x81],
+};
+/// The GUID of the ACPI 1.0 RSDP configuration table.
+pub const ACPI_TABLE_GUID: Guid = Guid {
+    a: 0xeb9d2d30,
+    b: 0x2d88,
+    c: 0x11d3,
+    d: [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+};
+/// The GUID of the SMBIOS 32-bit entry point configuration table.
+pub const SMBIOS_TABLE_GUID: Guid = Guid {
+    a: 0xeb9d2d31,
+    b: 0x2d88,
+    c: 0x11d3,
+    d: [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+};
+/// The GUID of the SMBIOS 64-bit entry point configuration table.
+pub const SMBIOS3_TABLE_GUID: Guid = Guid {
+    a: 0xf2fd1544,
+    b: 0x9794,
+    c: 0x4a2c,
+    d: [0x99, 0x2e, 0xe5, 0xbb, 0xcf, 0x20, 0xe3, 0x94],
+};
+/// The GUID of the device tree configuration table.
+pub const DEVICE_TREE_GUID: Guid = Guid {
+    a: 0xb1b621d5,
+    b: 0xf19c,
+    c: 0x41a5,
+    d: [0x83, 0x0b, 0xd9, 0x15, 0x2c, 0x69, 0xaa, 0xe0],
+};
+/// The GUID of the memory attributes table, which describes the permissions
+/// of runtime services code and data.
+pub const MEMORY_ATTRIBUTES_TABLE_GUID: Guid = Guid {
+    a: 0xdcfa911d,
+    b: 0x26eb,
+    c: 0x469f,
+    d: [0xa2, 0x20, 0x38, 0xb7, 0xdc, 0x46, 0x12, 0x20],
+};
+
+/// The status code returned by UEFI functions.
+#[repr(transparent)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Status(usize);
+impl Status {
+    const ERROR_BIT: usize = 1 << (usize::BITS - 1);
+
+    /// The operation succeeded.
+    pub const SUCCESS: Self = Self(0);
+    /// A parameter was incorrect.
+    pub const INVALID_PARAMETER: Self = Self(Self::ERROR_BIT | 2);
+    /// The operation is not supported.
+    pub const UNSUPPORTED: Self = Self(Self::ERROR_BIT | 3);
+    /// The buffer was not large enough to hold the result.
+    pub const BUFFER_TOO_SMALL: Self = Self(Self::ERROR_BIT | 5);
+    /// The hardware reported an error.
+    pub const DEVICE_ERROR: Self = Self(Self::ERROR_BIT | 7);
+    /// The item was not found.
+    pub const NOT_FOUND: Self = Self(Self::ERROR_BIT | 14);
+    /// The operation was refused for security reasons.
+    pub const SECURITY_VIOLATION: Self = Self(Self::ERROR_BIT | 26);
+
+    /// Create a status from its raw value.
+    pub const fn new(value: usize) -> Self {
+        Self(value)
+    }
+
+    /// The raw value of the status.
+    pub const fn value(self) -> usize {
+        self.0
+    }
+
+    /// Returns whether the status is an error, rather than success or a
+    /// warning.
+    pub const fn is_error(self) -> bool {
+        self.0 & Self::ERROR_BIT != 0
+    }
+}
+
+/// The header shared by the UEFI system table and service tables.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TableHeader {
+    /// The signature identifying the table.
+    pub signature: u64,
+    /// The UEFI revision the table conforms to, with the major version in
+    /// the upper 16 bits and the minor version in the lower 16 bits.
+    pub revision: u32,
+    /// The size of the table, including this header.
+    pub header_size: u32,
+    /// The CRC32 of the table.
+    pub crc32: u32,
+    reserved: u32,
+}
+
+#[repr(C)]
+struct RawSystemTable {
+    header: TableHeader,
+    firmware_vendor: u64,
+    firmware_revision: u32,
+    console_in_handle: u64,
+    con_in: u64,
+    console_out_handle: u64,
+    con_out: u64,
+    standard_error_handle: u64,
+    std_err: u64,
+    runtime_services: u64,
+    boot_services: u64,
+    number_of_table_entries: u64,
+    configuration_table: u64,
+}
+
+/// An entry of the configuration table array of the [`SystemTable`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ConfigurationTable {
+    /// The GUID identifying the table, such as [`ACPI_20_TABLE_GUID`].
+    pub guid: Guid,
+    /// The physical address of the table.
+    pub address: u64,
+}
+
+/// A NUL-terminated UCS-2 string, as used by UEFI.
+#[derive(Clone, Copy)]
+pub struct Ucs2Str<'a>(&'a [u16]);
+impl<'a> Ucs2Str<'a> {
+    /// Wrap the code units of a string, stopping at the first NUL if any.
+    pub fn new(units: &'a [u16]) -> Self {
+        let len = units.iter().position(|&u| u == 0).unwrap_or(units.len());
+        Self(&units[..len])
+    }
+
+    /// The code units of the string, excluding the NUL terminator.
+    pub fn as_slice(&self) -> &'a [u16] {
+        self.0
+    }
+
+    /// Decode the string, replacing invalid code units with
+    /// [`char::REPLACEMENT_CHARACTER`].
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        char::decode_utf16(self.0.iter().copied()).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
+impl fmt::Display for Ucs2Str<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars().try_for_each(|c| fmt::Write::write_char(f, c))
+    }
+}
+
+/// Encode `s` as a NUL-terminated UCS-2 string in `buf`, for passing to UEFI
+/// functions such as [`RuntimeServices::get_variable`]. Returns the encoded
+/// string, including its terminator.
+///
+/// # Examples
+/// ```rust
+/// let mut buf = [0u16; 16];
+/// let name = limine::efi::encode_ucs2("Boot0001", &mut buf).unwrap();
+/// assert_eq!(name.len(), 9);
+/// assert_eq!(name[0], b'B' as u16);
+/// ```
+pub fn encode_ucs2<'b>(s: &str, buf: &'b mut [u16]) -> Result<&'b [u16], Error> {
+    let needed = s.encode_utf16().count() + 1;
+    if buf.len() < needed {
+        return Err(Error::BufferTooSmall { needed });
+    }
+    for (dst, unit) in buf.iter_mut().zip(s.encode_utf16().chain([0])) {
+        *dst = unit;
+    }
+    Ok(&buf[..needed])
+}
+
+/// The UEFI system table, reached through the higher-half direct map.
+///
+/// # Examples
+/// ```rust
+/// use limine::{addr::PhysAddr, efi::{self, SystemTable}};
+/// # // Lay out a system table, vendor string and configuration table in fake
+/// # // physical memory at 0x30000.
+/// # let mut words = vec![0u64; 0x20];
+/// # words[0] = 0x5453_5953_2049_4249;
+/// # words[1] = (120 << 32) | (2 << 16) | 70;
+/// # words[3] = 0x30080;
+/// # words[4] = 0x10000;
+/// # words[13] = 1;
+/// # words[14] = 0x300c0;
+/// # for (i, unit) in "EDK II".encode_utf16().enumerate() {
+/// #     words[16 + i / 4] |= (unit as u64) << (16 * (i % 4));
+/// # }
+/// # unsafe { words.as_mut_ptr().add(24).cast::<efi::Guid>().write(efi::ACPI_20_TABLE_GUID) };
+/// # words[26] = 0xe0000;
+/// # let hhdm_offset = (words.as_ptr() as u64).wrapping_sub(0x30000);
+/// # let address = PhysAddr::new(0x30000);
+///
+/// // SAFETY: the system table is mapped in the higher-half direct map
+/// let system_table = unsafe { SystemTable::new(address, hhdm_offset) }.unwrap();
+/// assert_eq!(system_table.revision(), (2, 70));
+/// assert_eq!(system_table.firmware_vendor().unwrap().to_string(), "EDK II");
+///
+/// let rsdp = system_table.find_table(&efi::ACPI_20_TABLE_GUID);
+/// assert!(rsdp == Some(PhysAddr::new(0xe0000)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct SystemTable<'a> {
+    table: &'a RawSystemTable,
+    hhdm_offset: u64,
+}
+impl<'a> SystemTable<'a> {
+    /// The signature of the system table, `IBI SYST`.
+    pub const SIGNATURE: u64 = 0x5453_5953_2049_4249;
+
+    /// Validate the system table at `address`.
+    ///
+    /// # Safety
+    /// `hhdm_offset` must be the offset of a direct map covering the system
+    /// table, the firmware vendor string and the configuration table array,
+    /// such as the one returned by [`HhdmResponse::offset`], and they must not
+    /// be modified during `'a`.
+    pub unsafe fn new(address: PhysAddr, hhdm_offset: u64) -> Result<Self, Error> {
+        let table = &*translate::<RawSystemTable>(address, hhdm_offset)?;
+        if table.header.signature != Self::SIGNATURE {
+            return Err(Error::InvalidSignature);
+        }
+        if (table.header.header_size as usize) < size_of::<RawSystemTable>() {
+            return Err(Error::InvalidDescriptorSize {
+                size: table.header.header_size as usize,
+            });
+        }
+        Ok(Self { table, hhdm_offset })
+    }
+
+    /// Validate the system table given by the bootloader. This accepts both
+    /// physical addresses and addresses in the higher-half direct map.
+    ///
+    /// # Safety
+    /// See [`new`](Self::new).
+    pub unsafe fn from_response(
+        system_table: &EfiSystemTableResponse,
+        hhdm: &HhdmResponse,
+    ) -> Result<Self, Error> {
+        Self::new(
+            response_address(system_table.address() as u64, hhdm),
+            hhdm.offset(),
+        )
+    }
+
+    /// The header of the system table.
+    pub fn header(&self) -> TableHeader {
+        self.table.header
+    }
+
+    /// The UEFI revision, as `(major, minor)`. The minor version is multiplied
+    /// by 10, so that UEFI 2.7 is `(2, 70)`.
+    pub fn revision(&self) -> (u16, u16) {
+        let revision = self.table.header.revision;
+        ((revision >> 16) as u16, revision as u16)
+    }
+
+    /// The vendor of the firmware.
+    pub fn firmware_vendor(&self) -> Result<Ucs2Str<'a>, Error> {
+        let ptr = translate::<u16>(PhysAddr::new(self.table.firmware_vendor), self.hhdm_offset)?;
+        let len = (0..=MAX_STRING_LEN)
+            .find(|&i| unsafe { *ptr.add(i) } == 0)
+            .ok_or(Error::TooLong {
+                max: MAX_STRING_LEN,
+            })?;
+        Ok(Ucs2Str(unsafe { core::slice::from_raw_parts(ptr, len) }))
+    }
+
+    /// The vendor-specific revision of the firmware.
+    pub fn firmware_revision(&self) -> u32 {
+        self.table.firmware_revision
+    }
+
+    /// The configuration tables installed by the firmware.
+    pub fn configuration_tables(&self) -> &'a [ConfigurationTable] {
+        let count = self.table.number_of_table_entries as usize;
+        match translate(
+            PhysAddr::new(self.table.configuration_table),
+            self.hhdm_offset,
+        ) {
+            Ok(ptr) if count > 0 => unsafe { core::slice::from_raw_parts(ptr, count) },
+            _ => &[],
+        }
+    }
+
+    /// Find the physical address of the configuration table with the given
+    /// GUID.
+    pub fn find_table(&self, guid: &Guid) -> Option<PhysAddr> {
+        self.configuration_tables()
+            .iter()
+            .find(|table| table.guid == *guid)
+            .map(|table| PhysAddr::new(table.address))
+    }
+
+    /// The runtime services, which remain available after boot.
+    ///
+    /// # Safety
+    /// The runtime services table must be covered by the direct map, and the
+    /// firmware's runtime code and data must be mapped so that its function
+    /// pointers can be called: Limine does not call `SetVirtualAddressMap`, so
+    /// this usually means identity mapping the [`RUNTIME`](MemoryAttribute::RUNTIME)
+    /// regions of the EFI memory map.
+    pub unsafe fn runtime_services(&self) -> Result<RuntimeServices<'a>, Error> {
+        let table = translate::<RawRuntimeServices>(
+            PhysAddr::new(self.table.runtime_services),
+            self.hhdm_offset,
+        )?;
+        if (*table).header.signature != RuntimeServices::SIGNATURE {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(RuntimeServices {
+            table,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// A time, as returned by [`RuntimeServices::get_time`].
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    /// The year, from 1900 to 9999.
+    pub year: u16,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1 to 31.
+    pub day: u8,
+    /// The hour, from 0 to 23.
+    pub hour: u8,
+    /// The minute, from 0 to 59.
+    pub minute: u8,
+    /// The second, from 0 to 59.
+    pub second: u8,
+    pad1: u8,
+    /// The nanosecond, from 0 to 999,999,999.
+    pub nanosecond: u32,
+    /// The offset from UTC in minutes, or [`Time::UNSPECIFIED_TIMEZONE`] if
+    /// the time is local.
+    pub time_zone: i16,
+    /// The daylight saving time flags.
+    pub daylight: u8,
+    pad2: u8,
+}
+impl Time {
+    /// The value of [`time_zone`](Self::time_zone) for local time.
+    pub const UNSPECIFIED_TIMEZONE: i16 = 0x07ff;
+}
+
+/// The kind of reset performed by [`RuntimeServices::reset_system`].
+#[repr(transparent)]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct ResetType(u32);
+impl ResetType {
+    /// Reset every circuit in the system.
+    pub const COLD: Self = Self(0);
+    /// Reset the processors, but not necessarily the rest of the system.
+    pub const WARM: Self = Self(1);
+    /// Power the system off.
+    pub const SHUTDOWN: Self = Self(2);
+    /// A reset described by a GUID in the reset data.
+    pub const PLATFORM_SPECIFIC: Self = Self(3);
+}
+
+#[repr(C)]
+struct RawRuntimeServices {
+    header: TableHeader,
+    get_time: unsafe extern "efiapi" fn(*mut Time, *mut c_void) -> Status,
+    set_time: usize,
+    get_wakeup_time: usize,
+    set_wakeup_time: usize,
+    set_virtual_address_map: usize,
+    convert_pointer: usize,
+    get_variable: unsafe extern "efiapi" fn(
+        *const u16,
+        *const Guid,
+        *mut u32,
+        *mut usize,
+        *mut c_void,
+    ) -> Status,
+    get_next_variable_name: usize,
+    set_variable: usize,
+    get_next_high_monotonic_count: usize,
+    reset_system: unsafe extern "efiapi" fn(ResetType, Status, usize, *const c_void) -> !,
+}
+
+/// The UEFI runtime services, as returned by
+/// [`SystemTable::runtime_services`].
+///
+/// Runtime services are not reentrant, so this type is neither [`Send`] nor
+/// [`Sync`].
+pub struct RuntimeServices<'a> {
+    table: *const RawRuntimeServices,
+    _marker: PhantomData<&'a RawRuntimeServices>,
+}
+impl RuntimeServices<'_> {
+    /// The signature of the runtime services table, `RUNTSERV`.
+    pub const SIGNATURE: u64 = 0x5652_4553_544e_5552;
+
+    fn table(&self) -> &RawRuntimeServices {
+        unsafe { &*self.table }
+    }
+
+    /// The header of the runtime services table.
+    pub fn header(&self) -> TableHeader {
+        self.table().header
+    }
+
+    /// Read the current time from the hardware clock.
+    pub fn get_time(&self) -> Result<Time, Status> {
+        let mut time = Time::default();
+        let status = unsafe { (self.table().get_time)(&mut time, core::ptr::null_mut()) };
+        match status {
+            Status::SUCCESS => Ok(time),
+            status => Err(status),
+        }
+    }
+
+    /// Read the variable `name` from the namespace `vendor` into `buf`,
+    /// returning its attributes and data. `name` must be NUL-terminated, as
+    /// returned by [`encode_ucs2`].
+    ///
+    /// Fails with [`Status::BUFFER_TOO_SMALL`] if `buf` is too small; see
+    /// [`variable_size`](Self::variable_size).
+    pub fn get_variable<'b>(
+        &self,
+        name: &[u16],
+        vendor: &Guid,
+        buf: &'b mut [u8],
+    ) -> Result<(u32, &'b mut [u8]), Status> {
+        if name.last() != Some(&0) {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        let mut attributes = 0;
+        let len = buf.len();
+        let mut size = len;
+        let status = unsafe {
+            (self.table().get_variable)(
+                name.as_ptr(),
+                vendor,
+                &mut attributes,
+                &mut size,
+                buf.as_mut_ptr().cast(),
+            )
+        };
+        match status {
+            Status::SUCCESS => Ok((attributes, &mut buf[..size.min(len)])),
+            status => Err(status),
+        }
+    }
+
+    /// Returns the size of the variable `name` in the namespace `vendor`.
+    pub fn variable_size(&self, name: &[u16], vendor: &Guid) -> Result<usize, Status> {
+        if name.last() != Some(&0) {
+            return Err(Status::INVALID_PARAMETER);
+        }
+        let mut attributes = 0;
+        let mut size = 0;
+        let status = unsafe {
+            (self.table().get_variable)(
+                name.as_ptr(),
+                vendor,
+                &mut attributes,
+                &mut size,
+                core::ptr::null_mut(),
+            )
+        };
+        match status {
+            Status::SUCCESS | Status::BUFFER_TOO_SMALL => Ok(size),
+            status => Err(status),
+        }
+    }
+
+    /// Reset or power off the system. `data` is passed on to the firmware,
+    /// and usually holds a NUL-terminated UCS-2 string describing the reason.
+    pub fn reset_system(&self, ty: ResetType, status: Status, data: Option<&[u8]>) -> ! {
+        let (size, ptr) = data.map_or((0, core::ptr::null()), |data| (data.len(), data.as_ptr()));
+        unsafe { (self.table().reset_system)(ty, status, size, ptr.cast()) }
+    }
+}
@@ -0,0 +1,473 @@
+//! A read-only view of little-endian ELF64 images, such as the executable
+//! returned by [`ExecutableFileResponse::file`](crate::response::ExecutableFileResponse::file).
+//!
+//! The image is read in place: section data, symbol names and notes borrow
+//! from it, so no second copy of the executable is needed.
+//!
+//! # Examples
+//! ```rust
+//! use limine::elf::{self, Elf};
+//!
+//! // Inspect this very program
+//! let image = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+//! let elf = Elf::new(&image).unwrap();
+//!
+//! let text = elf.section_by_name(".text").unwrap();
+//! assert!(text.flags & elf::SHF_EXECINSTR != 0);
+//! assert!(elf.program_headers().any(|ph| ph.ty == elf::PT_LOAD));
+//!
+//! let main = elf.symbols().unwrap().iter().find(|sym| sym.name == "main").unwrap();
+//! assert!(main.is_function());
+//! ```
+
+use crate::{file::File, phys, Error};
+
+/// An inactive section header.
+pub const SHT_NULL: u32 = 0;
+/// A section holding data defined by the program.
+pub const SHT_PROGBITS: u32 = 1;
+/// A section holding the full symbol table.
+pub const SHT_SYMTAB: u32 = 2;
+/// A section holding a string table.
+pub const SHT_STRTAB: u32 = 3;
+/// A section holding notes.
+pub const SHT_NOTE: u32 = 7;
+/// A section which occupies no space in the file, such as `.bss`.
+pub const SHT_NOBITS: u32 = 8;
+/// A section holding the dynamic linking symbol table.
+pub const SHT_DYNSYM: u32 = 11;
+
+/// The section is writable at runtime.
+pub const SHF_WRITE: u64 = 0x1;
+/// The section occupies memory at runtime.
+pub const SHF_ALLOC: u64 = 0x2;
+/// The section holds executable code.
+pub const SHF_EXECINSTR: u64 = 0x4;
+
+/// A segment loaded into memory.
+pub const PT_LOAD: u32 = 1;
+/// A segment holding dynamic linking information.
+pub const PT_DYNAMIC: u32 = 2;
+/// A segment holding notes.
+pub const PT_NOTE: u32 = 4;
+/// A segment holding the thread-local storage template.
+pub const PT_TLS: u32 = 7;
+
+/// The segment is executable.
+pub const PF_X: u32 = 0x1;
+/// The segment is writable.
+pub const PF_W: u32 = 0x2;
+/// The segment is readable.
+pub const PF_R: u32 = 0x4;
+
+/// A symbol naming a data object.
+pub const STT_OBJECT: u8 = 1;
+/// A symbol naming a function.
+pub const STT_FUNC: u8 = 2;
+/// A symbol naming a section.
+pub const STT_SECTION: u8 = 3;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    phys::read_u16(data, offset).ok_or(Error::Truncated)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    phys::read_u32(data, offset).ok_or(Error::Truncated)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, Error> {
+    phys::read_u64(data, offset).ok_or(Error::Truncated)
+}
+
+fn read_bytes(data: &[u8], offset: u64, size: u64) -> Result<&[u8], Error> {
+    let end = offset.checked_add(size).ok_or(Error::Truncated)?;
+    data.get(usize::try_from(offset).map_err(|_| Error::Truncated)?..)
+        .and_then(|data| data.get(..usize::try_from(end - offset).ok()?))
+        .ok_or(Error::Truncated)
+}
+
+/// Read a NUL-terminated string from a string table, returning
+/// `"<invalid>"` if it is not UTF-8.
+fn string(strtab: &[u8], offset: u32) -> &str {
+    let name = strtab.get(offset as usize..).unwrap_or_default();
+    let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+    core::str::from_utf8(name).unwrap_or("<invalid>")
+}
+
+/// An ELF64 image.
+#[derive(Clone, Copy)]
+pub struct Elf<'a> {
+    image: &'a [u8],
+    phoff: usize,
+    phentsize: usize,
+    phnum: usize,
+    shoff: usize,
+    shentsize: usize,
+    shnum: usize,
+    shstrtab: &'a [u8],
+}
+impl<'a> Elf<'a> {
+    /// Parse the headers of an ELF image. Every program header, section header
+    /// and section must lie within `image`.
+    pub fn new(image: &'a [u8]) -> Result<Self, Error> {
+        if image.get(..4) != Some(b"\x7fELF") {
+            return Err(Error::InvalidSignature);
+        }
+        // EI_CLASS must be ELFCLASS64 and EI_DATA must be ELFDATA2LSB.
+        if image.get(4..6) != Some(&[2, 1]) {
+            return Err(Error::Unsupported);
+        }
+
+        let mut elf = Self {
+            image,
+            phoff: read_u64(image, 0x20)? as usize,
+            phentsize: read_u16(image, 0x36)? as usize,
+            phnum: read_u16(image, 0x38)? as usize,
+            shoff: read_u64(image, 0x28)? as usize,
+            shentsize: read_u16(image, 0x3a)? as usize,
+            shnum: read_u16(image, 0x3c)? as usize,
+            shstrtab: &[],
+        };
+        // Headers are at least as large as the ELF64 structures, and all of
+        // them must be in bounds, so offsets computed from them cannot
+        // overflow.
+        if (elf.phnum > 0 && elf.phentsize < 56) || (elf.shnum > 0 && elf.shentsize < 64) {
+            return Err(Error::Unsupported);
+        }
+        read_bytes(image, elf.phoff as u64, (elf.phnum * elf.phentsize) as u64)?;
+        read_bytes(image, elf.shoff as u64, (elf.shnum * elf.shentsize) as u64)?;
+        for index in 0..elf.shnum {
+            let (ty, offset, size) = elf.raw_section(index)?;
+            if ty != SHT_NOBITS {
+                read_bytes(image, offset, size)?;
+            }
+        }
+        let shstrndx = read_u16(image, 0x3e)? as usize;
+        if shstrndx != 0 {
+            let (_, offset, size) = elf.raw_section(shstrndx)?;
+            elf.shstrtab = read_bytes(image, offset, size)?;
+        }
+        Ok(elf)
+    }
+
+    /// Parse the headers of a file loaded by the bootloader, such as the
+    /// executable itself.
+    pub fn from_file(file: &'a File) -> Result<Self, Error> {
+        Self::new(file.data())
+    }
+
+    /// The whole image.
+    pub fn image(&self) -> &'a [u8] {
+        self.image
+    }
+
+    /// The object file type, such as 2 for executables and 3 for shared
+    /// objects and position-independent executables.
+    pub fn ty(&self) -> u16 {
+        read_u16(self.image, 0x10).unwrap()
+    }
+
+    /// The target architecture, such as 62 for x86-64, 183 for AArch64, 243
+    /// for RISC-V and 258 for LoongArch.
+    pub fn machine(&self) -> u16 {
+        read_u16(self.image, 0x12).unwrap()
+    }
+
+    /// The virtual address of the entry point.
+    pub fn entry(&self) -> u64 {
+        read_u64(self.image, 0x18).unwrap()
+    }
+
+    /// Returns the program headers, which describe the segments loaded into
+    /// memory.
+    pub fn program_headers(&self) -> impl Iterator<Item = ProgramHeader> + 'a {
+        let this = *self;
+        (0..self.phnum).map(move |index| {
+            let header = this.phoff + index * this.phentsize;
+            let u32_at = |offset| read_u32(this.image, header + offset).unwrap();
+            let u64_at = |offset| read_u64(this.image, header + offset).unwrap();
+            ProgramHeader {
+                ty: u32_at(0),
+                flags: u32_at(4),
+                offset: u64_at(8),
+                vaddr: u64_at(16),
+                paddr: u64_at(24),
+                filesz: u64_at(32),
+                memsz: u64_at(40),
+                align: u64_at(48),
+            }
+        })
+    }
+
+    /// Returns the section headers, including the null section at index 0.
+    pub fn sections(&self) -> impl Iterator<Item = Section<'a>> + 'a {
+        let this = *self;
+        (0..self.shnum).map(move |index| this.section(index).unwrap())
+    }
+
+    /// Returns the section header at `index`.
+    pub fn section(&self, index: usize) -> Option<Section<'a>> {
+        if index >= self.shnum {
+            return None;
+        }
+        let header = self.shoff + index * self.shentsize;
+        let u32_at = |offset| read_u32(self.image, header + offset).unwrap();
+        let u64_at = |offset| read_u64(self.image, header + offset).unwrap();
+        let (ty, offset, size) = self.raw_section(index).unwrap();
+        Some(Section {
+            name: string(self.shstrtab, u32_at(0)),
+            ty,
+            flags: u64_at(8),
+            addr: u64_at(16),
+            offset,
+            size,
+            link: u32_at(40),
+            info: u32_at(44),
+            addralign: u64_at(48),
+            entsize: u64_at(56),
+            data: match ty {
+                SHT_NOBITS => &[],
+                _ => read_bytes(self.image, offset, size).unwrap(),
+            },
+        })
+    }
+
+    /// Find the first section with the given name, such as `.text`.
+    pub fn section_by_name(&self, name: &str) -> Option<Section<'a>> {
+        self.sections().find(|section| section.name == name)
+    }
+
+    /// The full symbol table, if the image has not been stripped.
+    pub fn symbols(&self) -> Option<SymbolTable<'a>> {
+        self.symbol_table(SHT_SYMTAB)
+    }
+
+    /// The dynamic linking symbol table, if any.
+    pub fn dynamic_symbols(&self) -> Option<SymbolTable<'a>> {
+        self.symbol_table(SHT_DYNSYM)
+    }
+
+    /// Returns the notes in every note section.
+    pub fn notes(&self) -> impl Iterator<Item = Note<'a>> + 'a {
+        self.sections()
+            .filter(|section| section.ty == SHT_NOTE)
+            .flat_map(|section| Notes {
+                data: section.data,
+                align: if section.addralign == 8 { 8 } else { 4 },
+            })
+    }
+
+    fn symbol_table(&self, ty: u32) -> Option<SymbolTable<'a>> {
+        let symtab = self.sections().find(|section| section.ty == ty)?;
+        let strtab = self.section(symtab.link as usize)?;
+        (symtab.entsize >= 24).then_some(SymbolTable {
+            data: symtab.data,
+            entsize: symtab.entsize as usize,
+            strtab: strtab.data,
+        })
+    }
+
+    /// Returns the type, file offset and size of a section header.
+    fn raw_section(&self, index: usize) -> Result<(u32, u64, u64), Error> {
+        if index >= self.shnum {
+            return Err(Error::Truncated);
+        }
+        let header = self.shoff + index * self.shentsize;
+        Ok((
+            read_u32(self.image, header + 4)?,
+            read_u64(self.image, header + 24)?,
+            read_u64(self.image, header + 32)?,
+        ))
+    }
+}
+
+/// A program header, describing a segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramHeader {
+    /// The type of the segment, such as [`PT_LOAD`].
+    pub ty: u32,
+    /// The permissions of the segment, made of [`PF_R`], [`PF_W`] and
+    /// [`PF_X`].
+    pub flags: u32,
+    /// The offset of the segment in the file.
+    pub offset: u64,
+    /// The virtual address of the segment.
+    pub vaddr: u64,
+    /// The physical address of the segment.
+    pub paddr: u64,
+    /// The size of the segment in the file.
+    pub filesz: u64,
+    /// The size of the segment in memory.
+    pub memsz: u64,
+    /// The alignment of the segment.
+    pub align: u64,
+}
+
+/// A section header, together with the section's data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The name of the section, such as `.text`.
+    pub name: &'a str,
+    /// The type of the section, such as [`SHT_PROGBITS`].
+    pub ty: u32,
+    /// The flags of the section, made of `SHF_*` constants.
+    pub flags: u64,
+    /// The virtual address of the section, if it is loaded into memory.
+    pub addr: u64,
+    /// The offset of the section in the file.
+    pub offset: u64,
+    /// The size of the section.
+    pub size: u64,
+    /// The index of a related section, such as a symbol table's string table.
+    pub link: u32,
+    /// Extra information, depending on the type of the section.
+    pub info: u32,
+    /// The alignment of the section.
+    pub addralign: u64,
+    /// The size of each entry, for sections holding a table.
+    pub entsize: u64,
+    /// The contents of the section. This is empty for [`SHT_NOBITS`]
+    /// sections.
+    pub data: &'a [u8],
+}
+
+/// A symbol table, such as the one returned by [`Elf::symbols`].
+#[derive(Clone, Copy)]
+pub struct SymbolTable<'a> {
+    data: &'a [u8],
+    entsize: usize,
+    strtab: &'a [u8],
+}
+impl<'a> SymbolTable<'a> {
+    /// The number of symbols, including the null symbol at index 0.
+    pub fn len(&self) -> usize {
+        self.data.len() / self.entsize
+    }
+
+    /// Returns whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the symbol at `index`.
+    pub fn get(&self, index: usize) -> Option<Symbol<'a>> {
+        let entry = self.data.get(index.checked_mul(self.entsize)?..)?;
+        Some(Symbol {
+            name: string(self.strtab, read_u32(entry, 0).ok()?),
+            info: *entry.get(4)?,
+            other: *entry.get(5)?,
+            shndx: read_u16(entry, 6).ok()?,
+            value: read_u64(entry, 8).ok()?,
+            size: read_u64(entry, 16).ok()?,
+        })
+    }
+
+    /// Returns every symbol, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Symbol<'a>> + 'a {
+        let this = *self;
+        (0..self.len()).filter_map(move |index| this.get(index))
+    }
+
+    /// Find the symbol whose address range contains `addr`, preferring
+    /// functions. Symbols with a size of zero are ignored.
+    pub fn containing(&self, addr: u64) -> Option<Symbol<'a>> {
+        let mut found: Option<Symbol<'a>> = None;
+        for symbol in self.iter().filter(|symbol| symbol.contains(addr)) {
+            if symbol.is_function() {
+                return Some(symbol);
+            }
+            found.get_or_insert(symbol);
+        }
+        found
+    }
+}
+
+/// A symbol from a [`SymbolTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    /// The name of the symbol, which may be mangled.
+    pub name: &'a str,
+    /// The type of the symbol in the low 4 bits, and its binding in the high
+    /// 4 bits.
+    pub info: u8,
+    /// The visibility of the symbol.
+    pub other: u8,
+    /// The index of the section the symbol is defined in.
+    pub shndx: u16,
+    /// The value of the symbol, usually its address.
+    pub value: u64,
+    /// The size of the object the symbol names.
+    pub size: u64,
+}
+impl Symbol<'_> {
+    /// The type of the symbol, such as [`STT_FUNC`].
+    pub fn kind(&self) -> u8 {
+        self.info & 0xf
+    }
+
+    /// The binding of the symbol: 0 for local, 1 for global and 2 for weak.
+    pub fn binding(&self) -> u8 {
+        self.info >> 4
+    }
+
+    /// Returns whether the symbol names a function.
+    pub fn is_function(&self) -> bool {
+        self.kind() == STT_FUNC
+    }
+
+    /// Returns whether `addr` lies within the object the symbol names.
+    pub fn contains(&self, addr: u64) -> bool {
+        self.size != 0 && addr.wrapping_sub(self.value) < self.size
+    }
+}
+
+/// A note from a note section, as returned by [`Elf::notes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note<'a> {
+    /// The owner of the note, such as `GNU`, without its NUL terminator.
+    pub name: &'a [u8],
+    /// The type of the note, whose meaning depends on the owner.
+    pub ty: u32,
+    /// The contents of the note, such as the build ID for `GNU` notes of
+    /// type 3.
+    pub desc: &'a [u8],
+}
+
+struct Notes<'a> {
+    data: &'a [u8],
+    align: usize,
+}
+impl<'a> Iterator for Notes<'a> {
+    type Item = Note<'a>;
+
+    fn next(&mut self) -> Option<Note<'a>> {
+        let note = (|| {
+            let namesz = read_u32(self.data, 0).ok()? as usize;
+            let descsz = read_u32(self.data, 4).ok()? as usize;
+            let ty = read_u32(self.data, 8).ok()?;
+            let desc_start = (12usize.checked_add(namesz)?).next_multiple_of(self.align);
+            let desc_end = desc_start.checked_add(descsz)?;
+            let name = self.data.get(12..12 + namesz)?;
+            let desc = self.data.get(desc_start..desc_end)?;
+            let next = desc_end.next_multiple_of(self.align).min(self.data.len());
+            Some((
+                Note {
+                    name: name.strip_suffix(&[0]).unwrap_or(name),
+                    ty,
+                    desc,
+                },
+                next,
+            ))
+        })();
+        match note {
+            Some((note, next)) => {
+                self.data = &self.data[next..];
+                Some(note)
+            }
+            None => {
+                self.data = &[];
+                None
+            }
+        }
+    }
+}
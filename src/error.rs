@@ -0,0 +1,108 @@
+//! The crate-wide error type.
+
+use core::{
+    ffi::{c_char, CStr},
+    fmt,
+    str::Utf8Error,
+};
+
+/// The longest string, in bytes and excluding the NUL terminator, that will be
+/// read from a bootloader-provided pointer. Anything longer is assumed to be
+/// garbage.
+pub(crate) const MAX_STRING_LEN: usize = 0x10000;
+
+/// An error returned when a response contains data that cannot be trusted, or
+/// when it cannot be processed with the resources provided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A pointer that should have been valid was null.
+    NullPointer,
+    /// A string was not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+    /// A string had no NUL terminator within the given number of bytes.
+    TooLong {
+        /// The number of bytes that were searched.
+        max: usize,
+    },
+    /// A caller-provided buffer was too small to hold the result.
+    BufferTooSmall {
+        /// The number of elements the buffer needs to hold.
+        needed: usize,
+    },
+    /// A table's entries were smaller than the structure they must hold.
+    InvalidDescriptorSize {
+        /// The size of each entry, in bytes.
+        size: usize,
+    },
+    /// A table did not start with its expected signature.
+    InvalidSignature,
+    /// A table's checksum did not match its contents.
+    InvalidChecksum,
+    /// A table's length field was too small to hold its header.
+    InvalidLength,
+    /// A physical address could not be reached through the higher-half direct
+    /// map.
+    Unmapped,
+    /// Data ended before a structure or table it should contain.
+    Truncated,
+    /// A structure uses a version or format which is not supported.
+    Unsupported,
+    /// An executable had no symbol table, usually because it was stripped.
+    NoSymbols,
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NullPointer => f.write_str("unexpected null pointer"),
+            Self::InvalidUtf8(err) => write!(f, "invalid UTF-8: {err}"),
+            Self::TooLong { max } => write!(f, "string is longer than {max} bytes"),
+            Self::BufferTooSmall { needed } => {
+                write!(f, "buffer too small, {needed} elements needed")
+            }
+            Self::InvalidDescriptorSize { size } => {
+                write!(f, "descriptor size of {size} bytes is too small")
+            }
+            Self::InvalidSignature => f.write_str("invalid table signature"),
+            Self::InvalidChecksum => f.write_str("invalid table checksum"),
+            Self::InvalidLength => f.write_str("invalid table length"),
+            Self::Unmapped => f.write_str("address is outside the direct map"),
+            Self::Truncated => f.write_str("truncated data"),
+            Self::Unsupported => f.write_str("unsupported version or format"),
+            Self::NoSymbols => f.write_str("executable has no symbol table"),
+        }
+    }
+}
+impl From<Utf8Error> for Error {
+    fn from(err: Utf8Error) -> Self {
+        Self::InvalidUtf8(err)
+    }
+}
+
+/// Read a NUL-terminated string from a bootloader-provided pointer, checking
+/// for null pointers and refusing to scan more than [`MAX_STRING_LEN`] bytes.
+///
+/// # Safety
+/// If `ptr` is non-null, it must be valid for reads up to and including its
+/// NUL terminator or [`MAX_STRING_LEN`] bytes, whichever comes first.
+pub(crate) unsafe fn c_str<'a>(ptr: *const c_char) -> Result<&'a CStr, Error> {
+    if ptr.is_null() {
+        return Err(Error::NullPointer);
+    }
+    let len = (0..=MAX_STRING_LEN)
+        .find(|&i| *ptr.add(i) == 0)
+        .ok_or(Error::TooLong {
+            max: MAX_STRING_LEN,
+        })?;
+    Ok(CStr::from_bytes_with_nul_unchecked(
+        core::slice::from_raw_parts(ptr.cast(), len + 1),
+    ))
+}
+
+/// Like [`c_str`], but also require the string to be valid UTF-8.
+///
+/// # Safety
+/// See [`c_str`].
+pub(crate) unsafe fn str<'a>(ptr: *const c_char) -> Result<&'a str, Error> {
+    Ok(c_str(ptr)?.to_str()?)
+}
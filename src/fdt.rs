@@ -0,0 +1,566 @@
+//! A reader for the flattened device tree returned by the [device tree blob
+//! request](crate::request::DeviceTreeBlobRequest).
+//!
+//! The blob is read in place, without allocating. Malformed nodes and
+//! properties end iteration early rather than panicking.
+//!
+//! # Examples
+//! ```rust
+//! use limine::fdt::Fdt;
+//!
+//! # let blob = include_bytes!("fdt/example.dtb");
+//! let fdt = Fdt::new(blob).unwrap();
+//! assert_eq!(fdt.bootargs(), Some("console=ttyS0 quiet"));
+//!
+//! let memory = fdt.memory_regions().next().unwrap();
+//! assert_eq!((memory.address, memory.size), (0x8000_0000, Some(0x800_0000)));
+//!
+//! let serial = fdt.compatible_nodes("ns16550a").next().unwrap();
+//! assert_eq!(serial.name(), "serial@10000000");
+//! assert_eq!(serial.reg().next().unwrap().address, 0x1000_0000);
+//!
+//! let soc = fdt.find_node("/soc").unwrap();
+//! let range = soc.ranges().next().unwrap();
+//! assert_eq!((range.child_address, range.parent_address), (0x1000_0000, 0x1000_0000));
+//!
+//! let cpu = fdt.find_node("/cpus/cpu@0").unwrap();
+//! assert_eq!(cpu.property("device_type").unwrap().as_str(), Some("cpu"));
+//!
+//! let reservation = fdt.memory_reservations().next().unwrap();
+//! assert_eq!((reservation.address, reservation.size), (0x8000_0000, 0x10000));
+//! ```
+
+use crate::Error;
+
+/// The largest blob, in bytes, that [`Fdt::from_ptr`] will read. This is far
+/// larger than any real device tree, so a bigger size in the header means the
+/// pointer does not point to one.
+pub const MAX_SIZE: u32 = 0x100_0000;
+
+const MAGIC: u32 = 0xd00d_feed;
+const HEADER_SIZE: usize = 40;
+const MAX_DEPTH: usize = 32;
+
+const TOKEN_BEGIN_NODE: u32 = 1;
+const TOKEN_END_NODE: u32 = 2;
+const TOKEN_PROP: u32 = 3;
+const TOKEN_NOP: u32 = 4;
+const TOKEN_END: u32 = 9;
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+/// Read a number made of `cells` 32-bit cells. Numbers wider than 64 bits,
+/// such as PCI addresses, are truncated to their low 64 bits.
+fn read_cells(bytes: &[u8], cells: u32) -> u64 {
+    bytes
+        .chunks_exact(4)
+        .take(cells as usize)
+        .fold(0, |value, cell| {
+            value << 32 | u32::from_be_bytes(cell.try_into().unwrap()) as u64
+        })
+}
+
+fn str_at(data: &[u8], offset: usize) -> Option<&str> {
+    let bytes = data.get(offset..)?;
+    let len = bytes.iter().position(|&b| b == 0)?;
+    core::str::from_utf8(&bytes[..len]).ok()
+}
+
+fn align4(offset: usize) -> Option<usize> {
+    Some(offset.checked_add(3)? & !3)
+}
+
+enum Token<'a> {
+    BeginNode(&'a str),
+    EndNode,
+    Prop(Property<'a>),
+    Nop,
+    End,
+}
+
+#[derive(Clone, Copy)]
+struct Cells {
+    address: u32,
+    size: u32,
+}
+impl Cells {
+    /// The values assumed when a node has no `#address-cells` or
+    /// `#size-cells` property.
+    const DEFAULT: Self = Self {
+        address: 2,
+        size: 1,
+    };
+}
+
+/// A memory region which must not be used, from the memory reservation block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReservation {
+    /// The physical address of the region.
+    pub address: u64,
+    /// The size of the region, in bytes.
+    pub size: u64,
+}
+
+/// A flattened device tree.
+#[derive(Clone, Copy)]
+pub struct Fdt<'a> {
+    data: &'a [u8],
+    structure: &'a [u8],
+    strings: &'a [u8],
+    reservations: &'a [u8],
+}
+impl<'a> Fdt<'a> {
+    /// Validate the header of a device tree blob. Any bytes past the size
+    /// given in the header are ignored.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        match read_u32(data, 0) {
+            Some(MAGIC) => {}
+            Some(_) => return Err(Error::InvalidSignature),
+            None => return Err(Error::Truncated),
+        }
+        let header = |offset| read_u32(data, offset).ok_or(Error::Truncated);
+        let total_size = header(4)? as usize;
+        let data = data.get(..total_size).ok_or(Error::Truncated)?;
+        if total_size < HEADER_SIZE {
+            return Err(Error::Truncated);
+        }
+
+        let (version, last_compatible_version) = (header(20)?, header(24)?);
+        if version < 16 || last_compatible_version > 17 {
+            return Err(Error::Unsupported);
+        }
+
+        let section = |offset: u32, size: Option<u32>| {
+            let start = offset as usize;
+            let end = match size {
+                Some(size) => start.checked_add(size as usize).ok_or(Error::Truncated)?,
+                None => total_size,
+            };
+            data.get(start..end).ok_or(Error::Truncated)
+        };
+        Ok(Self {
+            data,
+            structure: section(header(8)?, (version >= 17).then_some(header(36)?))?,
+            strings: section(header(12)?, Some(header(32)?))?,
+            reservations: section(header(16)?, None)?,
+        })
+    }
+
+    /// Validate the header of a device tree blob in memory.
+    ///
+    /// # Safety
+    /// If `ptr` is not null and points to the FDT magic number, it must be
+    /// valid for reads of the total size given in the blob's header, for `'a`,
+    /// unless that size is larger than [`MAX_SIZE`].
+    pub unsafe fn from_ptr(ptr: *const u8) -> Result<Self, Error> {
+        if ptr.is_null() {
+            return Err(Error::NullPointer);
+        }
+        let header = core::slice::from_raw_parts(ptr, 8);
+        if read_u32(header, 0) != Some(MAGIC) {
+            return Err(Error::InvalidSignature);
+        }
+        let total_size = read_u32(header, 4).unwrap();
+        if total_size > MAX_SIZE {
+            return Err(Error::InvalidLength);
+        }
+        Self::new(core::slice::from_raw_parts(ptr, total_size as usize))
+    }
+
+    /// The bytes of the blob, up to its total size.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The total size of the blob, in bytes.
+    pub fn total_size(&self) -> u32 {
+        read_u32(self.data, 4).unwrap()
+    }
+
+    /// The version of the blob's format.
+    pub fn version(&self) -> u32 {
+        read_u32(self.data, 20).unwrap()
+    }
+
+    /// The physical ID of the boot CPU.
+    pub fn boot_cpuid_phys(&self) -> u32 {
+        read_u32(self.data, 28).unwrap()
+    }
+
+    /// Returns the entries of the memory reservation block.
+    pub fn memory_reservations(&self) -> impl Iterator<Item = MemoryReservation> + 'a {
+        let reservations = self.reservations;
+        (0..)
+            .map(move |i: usize| {
+                let offset = i.checked_mul(16)?;
+                Some(MemoryReservation {
+                    address: read_u64(reservations, offset)?,
+                    size: read_u64(reservations, offset.checked_add(8)?)?,
+                })
+            })
+            .take_while(|reservation| {
+                !matches!(
+                    reservation,
+                    None | Some(MemoryReservation {
+                        address: 0,
+                        size: 0
+                    })
+                )
+            })
+            .flatten()
+    }
+
+    fn token(&self, offset: usize) -> Option<(Token<'a>, usize)> {
+        let structure = self.structure;
+        let body = offset.checked_add(4)?;
+        match read_u32(structure, offset)? {
+            TOKEN_BEGIN_NODE => {
+                let name = str_at(structure, body)?;
+                Some((Token::BeginNode(name), align4(body + name.len() + 1)?))
+            }
+            TOKEN_END_NODE => Some((Token::EndNode, body)),
+            TOKEN_PROP => {
+                let len = read_u32(structure, body)? as usize;
+                let name = str_at(self.strings, read_u32(structure, body + 4)? as usize)?;
+                let start = body + 8;
+                let value = structure.get(start..start.checked_add(len)?)?;
+                Some((Token::Prop(Property { name, value }), align4(start + len)?))
+            }
+            TOKEN_NOP => Some((Token::Nop, body)),
+            TOKEN_END => Some((Token::End, body)),
+            _ => None,
+        }
+    }
+
+    /// Returns the offset just past the end of the node whose properties
+    /// start at `offset`.
+    fn skip_node(&self, mut offset: usize) -> Option<usize> {
+        let mut depth = 1;
+        while depth > 0 {
+            let (token, next) = self.token(offset)?;
+            match token {
+                Token::BeginNode(_) => depth += 1,
+                Token::EndNode => depth -= 1,
+                Token::Prop(_) | Token::Nop => {}
+                Token::End => return None,
+            }
+            offset = next;
+        }
+        Some(offset)
+    }
+
+    /// Returns the root node.
+    pub fn root(&self) -> Option<Node<'a>> {
+        let mut offset = 0;
+        loop {
+            match self.token(offset)? {
+                (Token::Nop, next) => offset = next,
+                (Token::BeginNode(name), props) => {
+                    return Some(Node {
+                        fdt: *self,
+                        name,
+                        props,
+                        parent_cells: Cells::DEFAULT,
+                    })
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Returns every node in the tree, depth first, starting with the root.
+    pub fn nodes(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        let fdt = *self;
+        let mut offset = 0;
+        let mut depth = 0;
+        let mut cells = [Cells::DEFAULT; MAX_DEPTH];
+        core::iter::from_fn(move || loop {
+            let (token, next) = fdt.token(offset)?;
+            offset = next;
+            match token {
+                Token::BeginNode(name) => {
+                    if depth + 1 >= MAX_DEPTH {
+                        return None;
+                    }
+                    let node = Node {
+                        fdt,
+                        name,
+                        props: next,
+                        parent_cells: cells[depth],
+                    };
+                    depth += 1;
+                    cells[depth] = node.cells();
+                    return Some(node);
+                }
+                Token::EndNode => depth = depth.checked_sub(1)?,
+                Token::Prop(_) | Token::Nop => {}
+                Token::End => return None,
+            }
+        })
+    }
+
+    /// Find a node by its path, such as `/soc/serial@10000000`. A path
+    /// component without a unit address, such as `memory`, matches the first
+    /// node with that name and any unit address.
+    pub fn find_node(&self, path: &str) -> Option<Node<'a>> {
+        let path = path.strip_prefix('/')?;
+        path.split('/')
+            .filter(|component| !component.is_empty())
+            .try_fold(self.root()?, |node, component| {
+                node.children().find(|child| {
+                    child.name() == component
+                        || (!component.contains('@')
+                            && child.name().split('@').next() == Some(component))
+                })
+            })
+    }
+
+    /// Returns every node whose `compatible` property contains `compatible`.
+    pub fn compatible_nodes<'s>(&self, compatible: &'s str) -> impl Iterator<Item = Node<'a>> + 's
+    where
+        'a: 's,
+    {
+        self.nodes()
+            .filter(move |node| node.is_compatible(compatible))
+    }
+
+    /// Returns the `/chosen` node, which holds parameters chosen by the
+    /// bootloader.
+    pub fn chosen(&self) -> Option<Node<'a>> {
+        self.find_node("/chosen")
+    }
+
+    /// Returns the `bootargs` property of the `/chosen` node.
+    pub fn bootargs(&self) -> Option<&'a str> {
+        self.chosen()?.property("bootargs")?.as_str()
+    }
+
+    /// Returns the `stdout-path` property of the `/chosen` node, which names
+    /// the node of the console device, optionally followed by `:` and its
+    /// options.
+    pub fn stdout_path(&self) -> Option<&'a str> {
+        self.chosen()?.property("stdout-path")?.as_str()
+    }
+
+    /// Returns the `reg` entries of every `/memory` node. Note that Limine's
+    /// [memory map](crate::response::MemoryMapResponse) should be preferred
+    /// for finding usable memory.
+    pub fn memory_regions(&self) -> impl Iterator<Item = Reg> + 'a {
+        self.root()
+            .into_iter()
+            .flat_map(|root| root.children())
+            .filter(|node| node.property("device_type").and_then(|p| p.as_str()) == Some("memory"))
+            .flat_map(|node| node.reg())
+    }
+}
+
+/// A property of a [`Node`].
+#[derive(Clone, Copy)]
+pub struct Property<'a> {
+    /// The name of the property.
+    pub name: &'a str,
+    /// The raw value of the property.
+    pub value: &'a [u8],
+}
+impl<'a> Property<'a> {
+    /// The value as a single big-endian `u32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.value.try_into().ok()?))
+    }
+
+    /// The value as a single big-endian `u64`, or a `u32` widened to `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value.len() {
+            4 => self.as_u32().map(u64::from),
+            _ => Some(u64::from_be_bytes(self.value.try_into().ok()?)),
+        }
+    }
+
+    /// The value as a single NUL-terminated string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let (&0, bytes) = self.value.split_last()? else {
+            return None;
+        };
+        core::str::from_utf8(bytes).ok()
+    }
+
+    /// The value as a list of NUL-terminated strings. Strings which are not
+    /// valid UTF-8 are skipped.
+    pub fn as_str_list(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let bytes = self.value.strip_suffix(&[0]).unwrap_or(&[]);
+        bytes
+            .split(|&b| b == 0)
+            .filter_map(|s| core::str::from_utf8(s).ok())
+    }
+}
+
+/// An entry of a node's `reg` property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reg {
+    /// The address of the region, in the address space of the node's parent.
+    pub address: u64,
+    /// The size of the region, or `None` if the parent has `#size-cells = <0>`.
+    pub size: Option<u64>,
+}
+
+/// An entry of a node's `ranges` property, mapping addresses of its children
+/// to addresses of its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    /// The start of the range in the address space of the node's children.
+    pub child_address: u64,
+    /// The start of the range in the address space of the node's parent.
+    pub parent_address: u64,
+    /// The size of the range, in bytes.
+    pub size: u64,
+}
+
+/// A node of a device tree.
+#[derive(Clone, Copy)]
+pub struct Node<'a> {
+    fdt: Fdt<'a>,
+    name: &'a str,
+    props: usize,
+    parent_cells: Cells,
+}
+impl<'a> Node<'a> {
+    /// The name of the node, including its unit address, such as
+    /// `serial@10000000`. The root node's name is empty.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The unit address of the node, which follows the `@` in its name.
+    pub fn unit_address(&self) -> Option<&'a str> {
+        self.name.split_once('@').map(|(_, address)| address)
+    }
+
+    /// Returns the properties of the node.
+    pub fn properties(&self) -> impl Iterator<Item = Property<'a>> + 'a {
+        let fdt = self.fdt;
+        let mut offset = self.props;
+        core::iter::from_fn(move || loop {
+            let (token, next) = fdt.token(offset)?;
+            offset = next;
+            match token {
+                Token::Prop(property) => return Some(property),
+                Token::Nop => {}
+                _ => return None,
+            }
+        })
+    }
+
+    /// Find a property by name.
+    pub fn property(&self, name: &str) -> Option<Property<'a>> {
+        self.properties().find(|property| property.name == name)
+    }
+
+    /// Returns the direct children of the node.
+    pub fn children(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        let fdt = self.fdt;
+        let cells = self.cells();
+        let mut offset = Some(self.props);
+        core::iter::from_fn(move || loop {
+            let (token, next) = fdt.token(offset?)?;
+            match token {
+                Token::BeginNode(name) => {
+                    offset = fdt.skip_node(next);
+                    return Some(Node {
+                        fdt,
+                        name,
+                        props: next,
+                        parent_cells: cells,
+                    });
+                }
+                Token::Prop(_) | Token::Nop => offset = Some(next),
+                Token::EndNode | Token::End => return None,
+            }
+        })
+    }
+
+    /// Returns the strings of the node's `compatible` property, from most to
+    /// least specific.
+    pub fn compatible(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.property("compatible")
+            .into_iter()
+            .flat_map(|property| property.as_str_list())
+    }
+
+    /// Returns whether the node's `compatible` property contains `compatible`.
+    pub fn is_compatible(&self, compatible: &str) -> bool {
+        self.compatible().any(|c| c == compatible)
+    }
+
+    fn cells(&self) -> Cells {
+        let cells = |name, default| {
+            self.property(name)
+                .and_then(|property| property.as_u32())
+                .unwrap_or(default)
+        };
+        Cells {
+            address: cells("#address-cells", Cells::DEFAULT.address),
+            size: cells("#size-cells", Cells::DEFAULT.size),
+        }
+    }
+
+    /// The number of cells used for addresses in the `reg` properties of the
+    /// node's children.
+    pub fn address_cells(&self) -> u32 {
+        self.cells().address
+    }
+
+    /// The number of cells used for sizes in the `reg` properties of the
+    /// node's children.
+    pub fn size_cells(&self) -> u32 {
+        self.cells().size
+    }
+
+    /// Returns the entries of the node's `reg` property, decoded with its
+    /// parent's `#address-cells` and `#size-cells`.
+    pub fn reg(&self) -> impl Iterator<Item = Reg> + 'a {
+        let Cells { address, size } = self.parent_cells;
+        let stride = (address as usize + size as usize) * 4;
+        self.property("reg")
+            .filter(|_| stride > 0)
+            .into_iter()
+            .flat_map(move |property| property.value.chunks_exact(stride.max(1)))
+            .map(move |entry| {
+                let (address_bytes, size_bytes) = entry.split_at(address as usize * 4);
+                Reg {
+                    address: read_cells(address_bytes, address),
+                    size: (size > 0).then(|| read_cells(size_bytes, size)),
+                }
+            })
+    }
+
+    /// Returns the entries of the node's `ranges` property. An empty `ranges`
+    /// property, meaning that the child and parent address spaces are
+    /// identical, yields no entries.
+    pub fn ranges(&self) -> impl Iterator<Item = Range> + 'a {
+        let child = self.cells();
+        let parent_address = self.parent_cells.address as usize * 4;
+        let (child_address, size) = (child.address as usize * 4, child.size as usize * 4);
+        let stride = child_address + parent_address + size;
+        self.property("ranges")
+            .filter(|_| stride > 0)
+            .into_iter()
+            .flat_map(move |property| property.value.chunks_exact(stride.max(1)))
+            .map(move |entry| {
+                let (child_bytes, rest) = entry.split_at(child_address);
+                let (parent_bytes, size_bytes) = rest.split_at(parent_address);
+                Range {
+                    child_address: read_cells(child_bytes, child.address),
+                    parent_address: read_cells(parent_bytes, (parent_address / 4) as u32),
+                    size: read_cells(size_bytes, child.size),
+                }
+            })
+    }
+}
@@ -6,6 +6,8 @@ use core::{
     num::NonZeroU32,
 };
 
+use crate::{error, Error};
+
 #[cfg(feature = "ipaddr")]
 use core::net::{Ipv4Addr, SocketAddrV4};
 
@@ -64,20 +66,20 @@ impl MediaType {
 /// [`ModuleRequest`](crate::request::ModuleRequest).
 #[repr(C)]
 pub struct File {
-    revision: u64,
-    addr: *mut c_void,
-    size: u64,
-    path: *const c_char,
-    string: *const c_char,
-    media_type: MediaType,
-    _unused: MaybeUninit<u32>,
-    tftp_ip: Option<NonZeroU32>,
-    tftp_port: Option<NonZeroU32>,
-    partition_idx: Option<NonZeroU32>,
-    mbr_disk_id: Option<NonZeroU32>,
-    gpt_disk_id: Uuid,
-    gpt_partition_id: Uuid,
-    partition_uuid: Uuid,
+    pub(crate) revision: u64,
+    pub(crate) addr: *mut c_void,
+    pub(crate) size: u64,
+    pub(crate) path: *const c_char,
+    pub(crate) string: *const c_char,
+    pub(crate) media_type: MediaType,
+    pub(crate) _unused: MaybeUninit<u32>,
+    pub(crate) tftp_ip: Option<NonZeroU32>,
+    pub(crate) tftp_port: Option<NonZeroU32>,
+    pub(crate) partition_idx: Option<NonZeroU32>,
+    pub(crate) mbr_disk_id: Option<NonZeroU32>,
+    pub(crate) gpt_disk_id: Uuid,
+    pub(crate) gpt_partition_id: Uuid,
+    pub(crate) partition_uuid: Uuid,
 }
 impl File {
     /// Get the revision of the file. Currently, this is always 0.
@@ -95,6 +97,14 @@ impl File {
         self.size
     }
 
+    /// The contents of the file.
+    pub fn data(&self) -> &[u8] {
+        if self.addr.is_null() {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(self.addr.cast(), self.size as usize) }
+    }
+
     /// The path of the file. This is the path that was passed to the bootloader
     /// in either the configuration file or the `internal_modules` field of the
     /// [`ModuleRequest`](crate::request::ModuleRequest).
@@ -102,6 +112,13 @@ impl File {
         unsafe { CStr::from_ptr(self.path) }
     }
 
+    /// The path of the file as a string, or an error if it is null,
+    /// unterminated or not valid UTF-8. See [`path`](Self::path) for more
+    /// information.
+    pub fn try_path(&self) -> Result<&str, Error> {
+        unsafe { error::str(self.path) }
+    }
+
     /// The string associated with this file. This is the command line that was passed
     /// to the bootloader in either the configuration file or the
     /// `internal_modules` field of the
@@ -179,3 +196,5 @@ impl File {
         self.partition_uuid.non_zero()
     }
 }
+
+crate::layout::check_layout!(file);
@@ -0,0 +1,408 @@
+//! Physical frame allocators seeded from the [memory
+//! map](crate::response::MemoryMapResponse).
+//!
+//! Two allocators are provided: [`BumpAllocator`], which hands out frames in
+//! order and never frees them, and [`BitmapAllocator`], which tracks every
+//! frame and supports freeing. Both store their metadata in memory carved out
+//! of the first [`USABLE`](EntryType::USABLE) region large enough to hold it,
+//! accessed through the higher-half direct map.
+//!
+//! Initially only usable memory is handed out. Once the bootloader's
+//! responses and the ACPI tables are no longer needed, the
+//! [`BOOTLOADER_RECLAIMABLE`](EntryType::BOOTLOADER_RECLAIMABLE) and
+//! [`ACPI_RECLAIMABLE`](EntryType::ACPI_RECLAIMABLE) regions can be added with
+//! `reclaim`. Reclaiming reads the memory map one last time, so the entries
+//! passed to it may live in the memory being reclaimed.
+//!
+//! This module is only available with the `frame_alloc` feature.
+
+use core::{mem::size_of, ptr::NonNull};
+
+use crate::{
+    addr::PhysAddr,
+    memory_map::{Entry, EntryType, PAGE_SIZE},
+    response::MemoryMapResponse,
+};
+
+/// An allocator of physical frames of [`PAGE_SIZE`] bytes.
+pub trait FrameAllocator {
+    /// Allocate a frame, returning its physical address, or `None` if no
+    /// memory is left.
+    fn allocate_frame(&mut self) -> Option<PhysAddr>;
+}
+
+/// Returns whether `ty` may be passed to `reclaim`.
+fn is_reclaimable(ty: EntryType) -> bool {
+    reclaim_bit(ty) != 0
+}
+
+/// Returns the bit recording that `ty` has been reclaimed, or 0 if `ty` may
+/// not be passed to `reclaim`.
+fn reclaim_bit(ty: EntryType) -> u8 {
+    if ty == EntryType::BOOTLOADER_RECLAIMABLE {
+        1 << 0
+    } else if ty == EntryType::ACPI_RECLAIMABLE {
+        1 << 1
+    } else {
+        0
+    }
+}
+
+/// Returns the page-aligned part of an entry as `(base, end)`, if any.
+fn frames(entry: &Entry) -> Option<(u64, u64)> {
+    let base = entry.base.checked_add(PAGE_SIZE - 1)? & !(PAGE_SIZE - 1);
+    let end = entry.end() & !(PAGE_SIZE - 1);
+    (end > base).then_some((base, end))
+}
+
+/// Carve `size` bytes, rounded up to whole frames, from the start of the first
+/// usable entry large enough, returning the physical range taken.
+fn carve(entries: &[&Entry], size: u64) -> Option<(u64, u64)> {
+    let size = size.checked_add(PAGE_SIZE - 1)? & !(PAGE_SIZE - 1);
+    entries
+        .iter()
+        .filter(|e| e.entry_type == EntryType::USABLE)
+        .filter_map(|e| frames(e))
+        .find(|(base, end)| end - base >= size)
+        .map(|(base, _)| (base, base + size))
+}
+
+/// Returns the part of `[base, end)` outside of `[hole_base, hole_end)`, as up
+/// to two ranges.
+fn subtract(base: u64, end: u64, hole_base: u64, hole_end: u64) -> [Option<(u64, u64)>; 2] {
+    let before = (base < hole_base.min(end)).then(|| (base, hole_base.min(end)));
+    let after = (hole_end.max(base) < end).then(|| (hole_end.max(base), end));
+    [before, after]
+}
+
+#[derive(Clone, Copy)]
+struct Region {
+    next: u64,
+    end: u64,
+}
+
+/// A frame allocator which hands out frames in order and never frees them.
+///
+/// # Examples
+/// ```rust
+/// use limine::{
+///     addr::PhysAddr,
+///     frame_alloc::{BumpAllocator, FrameAllocator},
+///     memory_map::{Entry, EntryType},
+/// };
+///
+/// // Pretend this buffer is the physical memory at 0x10000..0x20000
+/// let mut memory = vec![0u64; 0x10000 / 8];
+/// let hhdm_offset = (memory.as_mut_ptr() as u64).wrapping_sub(0x10000);
+///
+/// let usable = Entry { base: 0x10000, length: 0x10000, entry_type: EntryType::USABLE };
+/// let reclaimable = Entry {
+///     base: 0x30000,
+///     length: 0x2000,
+///     entry_type: EntryType::BOOTLOADER_RECLAIMABLE,
+/// };
+/// let entries = [&usable, &reclaimable];
+///
+/// let mut allocator = unsafe { BumpAllocator::new(&entries, hhdm_offset) }.unwrap();
+/// // The first frame holds the allocator's own metadata
+/// assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x11000)));
+/// assert_eq!((0..14).filter_map(|_| allocator.allocate_frame()).count(), 14);
+/// assert_eq!(allocator.allocate_frame(), None);
+///
+/// unsafe { allocator.reclaim(&entries, EntryType::BOOTLOADER_RECLAIMABLE) };
+/// assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x30000)));
+/// assert_eq!(allocator.allocate_frame(), Some(PhysAddr::new(0x31000)));
+///
+/// // Reclaiming the same type again does nothing
+/// unsafe { allocator.reclaim(&entries, EntryType::BOOTLOADER_RECLAIMABLE) };
+/// assert_eq!(allocator.allocate_frame(), None);
+/// ```
+pub struct BumpAllocator {
+    regions: NonNull<Region>,
+    capacity: usize,
+    len: usize,
+    current: usize,
+    reclaimed: u8,
+    hhdm_offset: u64,
+}
+unsafe impl Send for BumpAllocator {}
+unsafe impl Sync for BumpAllocator {}
+impl BumpAllocator {
+    /// Create a new bump allocator handing out the usable frames in `entries`.
+    /// Returns `None` if no usable region can hold the allocator's metadata.
+    ///
+    /// # Safety
+    /// `entries` must accurately describe physical memory, and `hhdm_offset`
+    /// must be the offset of a direct map covering all usable memory, such as
+    /// the one returned by [`HhdmResponse::offset`](crate::response::HhdmResponse::offset).
+    pub unsafe fn new(entries: &[&Entry], hhdm_offset: u64) -> Option<Self> {
+        // Reserve room for every region that may ever be added. Carving can
+        // split a usable region in two.
+        let capacity = entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::USABLE || is_reclaimable(e.entry_type))
+            .count()
+            + 1;
+        let (meta_base, meta_end) = carve(entries, (capacity * size_of::<Region>()) as u64)?;
+
+        let mut allocator = Self {
+            regions: NonNull::new(meta_base.wrapping_add(hhdm_offset) as *mut Region)?,
+            capacity,
+            len: 0,
+            current: 0,
+            reclaimed: 0,
+            hhdm_offset,
+        };
+        for entry in entries.iter().filter(|e| e.entry_type == EntryType::USABLE) {
+            if let Some((base, end)) = frames(entry) {
+                for range in subtract(base, end, meta_base, meta_end)
+                    .into_iter()
+                    .flatten()
+                {
+                    allocator.push(range);
+                }
+            }
+        }
+        Some(allocator)
+    }
+
+    /// Create a new bump allocator from a memory map response. See
+    /// [`new`](Self::new).
+    ///
+    /// # Safety
+    /// See [`new`](Self::new).
+    pub unsafe fn from_response(response: &MemoryMapResponse, hhdm_offset: u64) -> Option<Self> {
+        Self::new(response.entries(), hhdm_offset)
+    }
+
+    /// Start handing out the frames of every entry of type `ty`, which must be
+    /// [`BOOTLOADER_RECLAIMABLE`](EntryType::BOOTLOADER_RECLAIMABLE) or
+    /// [`ACPI_RECLAIMABLE`](EntryType::ACPI_RECLAIMABLE); other types, and
+    /// types which have already been reclaimed, are ignored.
+    ///
+    /// # Safety
+    /// Nothing in the reclaimed regions may be used after this call, except
+    /// for reading `entries` during it. `entries` must be the same memory map
+    /// the allocator was created from.
+    pub unsafe fn reclaim(&mut self, entries: &[&Entry], ty: EntryType) {
+        let bit = reclaim_bit(ty);
+        if bit == 0 || self.reclaimed & bit != 0 {
+            return;
+        }
+        self.reclaimed |= bit;
+        for entry in entries.iter().filter(|e| e.entry_type == ty) {
+            if let Some(range) = frames(entry) {
+                self.push(range);
+            }
+        }
+    }
+
+    /// The physical address of the allocator's metadata.
+    pub fn metadata_addr(&self) -> PhysAddr {
+        PhysAddr::new((self.regions.as_ptr() as u64).wrapping_sub(self.hhdm_offset))
+    }
+
+    fn push(&mut self, (next, end): (u64, u64)) {
+        if self.len < self.capacity {
+            unsafe {
+                self.regions
+                    .as_ptr()
+                    .add(self.len)
+                    .write(Region { next, end })
+            };
+            self.len += 1;
+        }
+    }
+}
+impl FrameAllocator for BumpAllocator {
+    fn allocate_frame(&mut self) -> Option<PhysAddr> {
+        while self.current < self.len {
+            let region = unsafe { &mut *self.regions.as_ptr().add(self.current) };
+            if region.next < region.end {
+                let frame = region.next;
+                region.next += PAGE_SIZE;
+                return Some(PhysAddr::new(frame));
+            }
+            self.current += 1;
+        }
+        None
+    }
+}
+
+/// A frame allocator which tracks every frame with one bit, and supports
+/// freeing.
+///
+/// The bitmap spans from the lowest to the highest frame of all usable and
+/// reclaimable entries, so that reclaimable regions can be added later
+/// without growing it.
+///
+/// # Examples
+/// ```rust
+/// use limine::{
+///     addr::PhysAddr,
+///     frame_alloc::{BitmapAllocator, FrameAllocator},
+///     memory_map::{Entry, EntryType},
+/// };
+///
+/// // Pretend this buffer is the physical memory at 0x10000..0x20000
+/// let mut memory = vec![0u64; 0x10000 / 8];
+/// let hhdm_offset = (memory.as_mut_ptr() as u64).wrapping_sub(0x10000);
+///
+/// let usable = Entry { base: 0x10000, length: 0x10000, entry_type: EntryType::USABLE };
+/// let mut allocator = unsafe { BitmapAllocator::new(&[&usable], hhdm_offset) }.unwrap();
+/// assert_eq!(allocator.free_frames(), 15);
+///
+/// let frame = allocator.allocate_frame().unwrap();
+/// assert_eq!(frame, PhysAddr::new(0x11000));
+/// assert_eq!(allocator.free_frames(), 14);
+///
+/// // SAFETY: the frame came from this allocator and is no longer used
+/// unsafe { allocator.deallocate_frame(frame) };
+/// assert_eq!(allocator.allocate_frame(), Some(frame));
+/// ```
+pub struct BitmapAllocator {
+    bitmap: NonNull<u64>,
+    words: usize,
+    base: u64,
+    frames: u64,
+    free: u64,
+    hint: usize,
+    reclaimed: u8,
+    hhdm_offset: u64,
+}
+unsafe impl Send for BitmapAllocator {}
+unsafe impl Sync for BitmapAllocator {}
+impl BitmapAllocator {
+    /// Create a new bitmap allocator handing out the usable frames in
+    /// `entries`. Returns `None` if there is no usable memory, or no usable
+    /// region can hold the bitmap.
+    ///
+    /// # Safety
+    /// `entries` must accurately describe physical memory, and `hhdm_offset`
+    /// must be the offset of a direct map covering all usable memory, such as
+    /// the one returned by [`HhdmResponse::offset`](crate::response::HhdmResponse::offset).
+    pub unsafe fn new(entries: &[&Entry], hhdm_offset: u64) -> Option<Self> {
+        let (base, end) = entries
+            .iter()
+            .filter(|e| e.entry_type == EntryType::USABLE || is_reclaimable(e.entry_type))
+            .filter_map(|e| frames(e))
+            .reduce(|(base, end), (b, e)| (base.min(b), end.max(e)))?;
+        let frames = (end - base) / PAGE_SIZE;
+        let words = frames.div_ceil(64) as usize;
+        let (meta_base, meta_end) = carve(entries, (words * size_of::<u64>()) as u64)?;
+
+        let mut allocator = Self {
+            bitmap: NonNull::new(meta_base.wrapping_add(hhdm_offset) as *mut u64)?,
+            words,
+            base,
+            frames,
+            free: 0,
+            hint: 0,
+            reclaimed: 0,
+            hhdm_offset,
+        };
+        core::ptr::write_bytes(allocator.bitmap.as_ptr(), 0xff, words);
+        allocator.release(entries, EntryType::USABLE);
+        allocator.mark(meta_base, meta_end, true);
+        Some(allocator)
+    }
+
+    /// Create a new bitmap allocator from a memory map response. See
+    /// [`new`](Self::new).
+    ///
+    /// # Safety
+    /// See [`new`](Self::new).
+    pub unsafe fn from_response(response: &MemoryMapResponse, hhdm_offset: u64) -> Option<Self> {
+        Self::new(response.entries(), hhdm_offset)
+    }
+
+    /// Mark the frames of every entry of type `ty` as free. `ty` must be
+    /// [`BOOTLOADER_RECLAIMABLE`](EntryType::BOOTLOADER_RECLAIMABLE) or
+    /// [`ACPI_RECLAIMABLE`](EntryType::ACPI_RECLAIMABLE); other types, and
+    /// types which have already been reclaimed, are ignored.
+    ///
+    /// # Safety
+    /// Nothing in the reclaimed regions may be used after this call, except
+    /// for reading `entries` during it. `entries` must be the same memory map
+    /// the allocator was created from.
+    pub unsafe fn reclaim(&mut self, entries: &[&Entry], ty: EntryType) {
+        let bit = reclaim_bit(ty);
+        if bit != 0 && self.reclaimed & bit == 0 {
+            self.reclaimed |= bit;
+            self.release(entries, ty);
+        }
+    }
+
+    /// Free a frame previously returned by
+    /// [`allocate_frame`](FrameAllocator::allocate_frame). Addresses outside
+    /// of the bitmap, or of frames which are already free, are ignored.
+    ///
+    /// # Safety
+    /// `frame` must have been returned by `allocate_frame` on this allocator,
+    /// and must not be used after this call. The bitmap also covers holes in
+    /// the memory map and the bitmap itself, so freeing any other frame would
+    /// let the allocator hand out reserved memory or its own metadata.
+    pub unsafe fn deallocate_frame(&mut self, frame: PhysAddr) {
+        let frame = frame.as_u64();
+        self.mark(frame, frame.saturating_add(PAGE_SIZE), false);
+    }
+
+    /// The number of frames currently free.
+    pub fn free_frames(&self) -> u64 {
+        self.free
+    }
+
+    /// The physical address of the bitmap.
+    pub fn metadata_addr(&self) -> PhysAddr {
+        PhysAddr::new((self.bitmap.as_ptr() as u64).wrapping_sub(self.hhdm_offset))
+    }
+
+    fn bitmap(&mut self) -> &mut [u64] {
+        unsafe { core::slice::from_raw_parts_mut(self.bitmap.as_ptr(), self.words) }
+    }
+
+    fn release(&mut self, entries: &[&Entry], ty: EntryType) {
+        for entry in entries.iter().filter(|e| e.entry_type == ty) {
+            if let Some((base, end)) = frames(entry) {
+                self.mark(base, end, false);
+            }
+        }
+    }
+
+    /// Mark the frames in `[base, end)` as used or free, clamped to the bitmap.
+    fn mark(&mut self, base: u64, end: u64, used: bool) {
+        let first = base.saturating_sub(self.base) / PAGE_SIZE;
+        let last = (end.saturating_sub(self.base) / PAGE_SIZE).min(self.frames);
+        for frame in first..last {
+            let (word, bit) = ((frame / 64) as usize, frame % 64);
+            let was_used = self.bitmap()[word] & (1 << bit) != 0;
+            if used && !was_used {
+                self.bitmap()[word] |= 1 << bit;
+                self.free -= 1;
+            } else if !used && was_used {
+                self.bitmap()[word] &= !(1 << bit);
+                self.free += 1;
+                self.hint = self.hint.min(word);
+            }
+        }
+    }
+}
+impl FrameAllocator for BitmapAllocator {
+    fn allocate_frame(&mut self) -> Option<PhysAddr> {
+        let (hint, frames) = (self.hint, self.frames);
+        let (word, bits) = self.bitmap()[hint..]
+            .iter_mut()
+            .enumerate()
+            .find(|(_, bits)| **bits != u64::MAX)?;
+        let word = hint + word;
+        let bit = bits.trailing_ones() as u64;
+        let frame = word as u64 * 64 + bit;
+        if frame >= frames {
+            return None;
+        }
+        *bits |= 1 << bit;
+        self.free -= 1;
+        self.hint = word;
+        Some(PhysAddr::new(self.base + frame * PAGE_SIZE))
+    }
+}
@@ -2,43 +2,45 @@
 
 use core::{
     ffi::c_void,
-    marker::{Send, Sync},
+    marker::{PhantomData, Send, Sync},
     mem::MaybeUninit,
     ptr::NonNull,
 };
 
+pub mod console;
+
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub(crate) struct RawFramebufferV0 {
-    addr: *mut c_void,
-    width: u64,
-    height: u64,
-    pitch: u64,
-    bpp: u16,
-    memory_model: MemoryModel,
-    red_mask_size: u8,
-    red_mask_shift: u8,
-    green_mask_size: u8,
-    green_mask_shift: u8,
-    blue_mask_size: u8,
-    blue_mask_shift: u8,
-    _unused: MaybeUninit<[u8; 7]>,
-    edid_size: u64,
-    edid: Option<NonNull<u8>>,
+    pub(crate) addr: *mut c_void,
+    pub(crate) width: u64,
+    pub(crate) height: u64,
+    pub(crate) pitch: u64,
+    pub(crate) bpp: u16,
+    pub(crate) memory_model: MemoryModel,
+    pub(crate) red_mask_size: u8,
+    pub(crate) red_mask_shift: u8,
+    pub(crate) green_mask_size: u8,
+    pub(crate) green_mask_shift: u8,
+    pub(crate) blue_mask_size: u8,
+    pub(crate) blue_mask_shift: u8,
+    pub(crate) _unused: MaybeUninit<[u8; 7]>,
+    pub(crate) edid_size: u64,
+    pub(crate) edid: Option<NonNull<u8>>,
 }
 
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub(crate) struct RawFramebufferV1 {
-    _v0: RawFramebufferV0,
-    mode_ct: u64,
-    modes: *const *const VideoMode,
+    pub(crate) _v0: RawFramebufferV0,
+    pub(crate) mode_ct: u64,
+    pub(crate) modes: *const *const VideoMode,
 }
 
 #[repr(C)]
 pub(crate) union RawFramebuffer {
-    v0: RawFramebufferV0,
-    v1: RawFramebufferV1,
+    pub(crate) v0: RawFramebufferV0,
+    pub(crate) v1: RawFramebufferV1,
 }
 
 /// A memory model used by a framebuffer. Currently only
@@ -88,6 +90,133 @@ pub struct VideoMode {
     pub blue_mask_shift: u8,
 }
 
+impl VideoMode {
+    /// The number of bytes of framebuffer memory this mode needs, or `None` on
+    /// overflow.
+    pub fn buffer_size(&self) -> Option<u64> {
+        self.pitch.checked_mul(self.height)
+    }
+}
+
+/// Criteria for choosing between the [video modes](Framebuffer::modes) of a
+/// framebuffer.
+///
+/// # Examples
+/// ```rust
+/// use limine::framebuffer::{MemoryModel, ModeFilter, VideoMode};
+///
+/// let mode = |width, height, bpp: u16| VideoMode {
+///     pitch: width * bpp as u64 / 8,
+///     width,
+///     height,
+///     bpp,
+///     memory_model: MemoryModel::RGB,
+///     red_mask_size: 8,
+///     red_mask_shift: 16,
+///     green_mask_size: 8,
+///     green_mask_shift: 8,
+///     blue_mask_size: 8,
+///     blue_mask_shift: 0,
+/// };
+/// let (small, wide, large_24) = (mode(800, 600, 32), mode(1920, 1080, 32), mode(2560, 1440, 24));
+/// let modes = [&small, &wide, &large_24];
+///
+/// // The largest 32 bpp RGB mode
+/// let filter = ModeFilter::new().with_bpp(32).with_memory_model(MemoryModel::RGB);
+/// assert_eq!(filter.largest(&modes).unwrap().width, 1920);
+///
+/// // The mode closest to a preferred resolution, such as one from the EDID
+/// let closest = ModeFilter::new().closest(&modes, 1680, 1050).unwrap();
+/// assert_eq!((closest.width, closest.height), (1920, 1080));
+///
+/// // Nothing matches
+/// assert!(ModeFilter::new().with_min_size(3840, 2160).largest(&modes).is_none());
+/// assert_eq!(wide.buffer_size(), Some(1920 * 4 * 1080));
+/// ```
+#[derive(Clone, Copy)]
+pub struct ModeFilter {
+    min_size: (u64, u64),
+    max_size: (u64, u64),
+    bpp: Option<u16>,
+    memory_model: Option<MemoryModel>,
+}
+impl ModeFilter {
+    /// Create a filter which accepts every mode.
+    pub const fn new() -> Self {
+        Self {
+            min_size: (0, 0),
+            max_size: (u64::MAX, u64::MAX),
+            bpp: None,
+            memory_model: None,
+        }
+    }
+
+    /// Only accept modes at least `width` by `height` pixels.
+    pub const fn with_min_size(mut self, width: u64, height: u64) -> Self {
+        self.min_size = (width, height);
+        self
+    }
+
+    /// Only accept modes at most `width` by `height` pixels.
+    pub const fn with_max_size(mut self, width: u64, height: u64) -> Self {
+        self.max_size = (width, height);
+        self
+    }
+
+    /// Only accept modes with the given number of bits per pixel.
+    pub const fn with_bpp(mut self, bpp: u16) -> Self {
+        self.bpp = Some(bpp);
+        self
+    }
+
+    /// Only accept modes with the given memory model.
+    pub const fn with_memory_model(mut self, memory_model: MemoryModel) -> Self {
+        self.memory_model = Some(memory_model);
+        self
+    }
+
+    /// Returns whether a mode is accepted by the filter.
+    pub fn matches(&self, mode: &VideoMode) -> bool {
+        (self.min_size.0..=self.max_size.0).contains(&mode.width)
+            && (self.min_size.1..=self.max_size.1).contains(&mode.height)
+            && self.bpp.map_or(true, |bpp| mode.bpp == bpp)
+            && self
+                .memory_model
+                .map_or(true, |model| mode.memory_model == model)
+    }
+
+    /// Returns the modes accepted by the filter.
+    pub fn filter<'m>(
+        self,
+        modes: &'m [&'m VideoMode],
+    ) -> impl Iterator<Item = &'m VideoMode> + 'm {
+        modes.iter().copied().filter(move |mode| self.matches(mode))
+    }
+
+    /// Returns the accepted mode with the most pixels, preferring higher bpp
+    /// between modes of the same size.
+    pub fn largest<'m>(self, modes: &'m [&'m VideoMode]) -> Option<&'m VideoMode> {
+        self.filter(modes)
+            .max_by_key(|mode| (mode.width.saturating_mul(mode.height), mode.bpp))
+    }
+
+    /// Returns the accepted mode closest in size to `width` by `height`,
+    /// preferring larger modes and then higher bpp between modes equally far
+    /// away.
+    pub fn closest<'m>(
+        self,
+        modes: &'m [&'m VideoMode],
+        width: u64,
+        height: u64,
+    ) -> Option<&'m VideoMode> {
+        self.filter(modes).min_by_key(|mode| {
+            let distance = mode.width.abs_diff(width) + mode.height.abs_diff(height);
+            let smaller = mode.width < width || mode.height < height;
+            (distance, smaller, u16::MAX - mode.bpp)
+        })
+    }
+}
+
 /// A pointer to a framebuffer.
 ///
 /// # Why is this a wrapper type?
@@ -169,7 +298,27 @@ impl<'a> Framebuffer<'a> {
         unsafe { self.inner.v0 }.blue_mask_shift
     }
 
+    /// The current mode of the framebuffer, for comparing against
+    /// [`modes`](Self::modes).
+    pub fn mode(&self) -> VideoMode {
+        let v0 = unsafe { self.inner.v0 };
+        VideoMode {
+            pitch: v0.pitch,
+            width: v0.width,
+            height: v0.height,
+            bpp: v0.bpp,
+            memory_model: v0.memory_model,
+            red_mask_size: v0.red_mask_size,
+            red_mask_shift: v0.red_mask_shift,
+            green_mask_size: v0.green_mask_size,
+            green_mask_shift: v0.green_mask_shift,
+            blue_mask_size: v0.blue_mask_size,
+            blue_mask_shift: v0.blue_mask_shift,
+        }
+    }
+
     /// The raw EDID bytes of the display attached to this framebuffer.
+    /// These can be decoded with [`Edid::parse`](crate::edid::Edid::parse).
     pub fn edid(&self) -> Option<&[u8]> {
         unsafe {
             self.inner.v0.edid.map(|ptr| {
@@ -178,6 +327,28 @@ impl<'a> Framebuffer<'a> {
         }
     }
 
+    /// The pixel format of the framebuffer in the current mode, or `None` if it
+    /// is not supported by [`PixelFormat`].
+    pub fn pixel_format(&self) -> Option<PixelFormat> {
+        PixelFormat::from_mode(&self.mode())
+    }
+
+    /// Returns a [`Canvas`] for drawing on the framebuffer in its current mode,
+    /// or `None` if its pixel format is not supported.
+    ///
+    /// # Safety
+    /// Nothing else may access the framebuffer memory while the canvas is
+    /// alive, including through other canvases.
+    pub unsafe fn canvas(&self) -> Option<Canvas<'a>> {
+        Canvas::from_raw_parts(
+            self.addr(),
+            usize::try_from(self.width()).ok()?,
+            usize::try_from(self.height()).ok()?,
+            usize::try_from(self.pitch()).ok()?,
+            self.pixel_format()?,
+        )
+    }
+
     /// The video modes supported on this framebuffer. Only available on
     /// revision 1 and above.
     pub fn modes(&self) -> Option<&[&VideoMode]> {
@@ -194,3 +365,293 @@ impl<'a> Framebuffer<'a> {
 }
 unsafe impl Send for Framebuffer<'_> {}
 unsafe impl Sync for Framebuffer<'_> {}
+
+/// A colour with 8 bits per channel.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Rgb {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+}
+impl Rgb {
+    /// Black.
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    /// White.
+    pub const WHITE: Self = Self::new(0xff, 0xff, 0xff);
+
+    /// Create a new colour.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+/// The layout of a pixel in framebuffer memory, as described by the bpp and
+/// the mask fields of a [`Framebuffer`] or [`VideoMode`].
+///
+/// # Examples
+/// ```rust
+/// use limine::framebuffer::{PixelFormat, Rgb};
+///
+/// // RGB565
+/// let format = PixelFormat::new(16, (5, 11), (6, 5), (5, 0)).unwrap();
+/// assert_eq!(format.pack(Rgb::new(0xff, 0, 0)), 0xf800);
+/// assert_eq!(format.pack(Rgb::new(0, 0xff, 0)), 0x07e0);
+/// assert_eq!(format.unpack(0x001f), Rgb::new(0, 0, 0xf8));
+///
+/// // Masks must fit in the pixel
+/// assert!(PixelFormat::new(16, (8, 16), (8, 8), (8, 0)).is_none());
+/// ```
+#[derive(Clone, Copy)]
+pub struct PixelFormat {
+    bpp: u16,
+    red: (u8, u8),
+    green: (u8, u8),
+    blue: (u8, u8),
+}
+impl PixelFormat {
+    /// Create a pixel format from a bpp and the `(size, shift)` of each
+    /// channel's mask. Returns `None` unless `bpp` is 16, 24 or 32 and every
+    /// mask fits in a pixel.
+    pub const fn new(bpp: u16, red: (u8, u8), green: (u8, u8), blue: (u8, u8)) -> Option<Self> {
+        if !matches!(bpp, 16 | 24 | 32) {
+            return None;
+        }
+        let masks = [red, green, blue];
+        let mut i = 0;
+        while i < masks.len() {
+            let (size, shift) = masks[i];
+            if size == 0 || size > 16 || size as u16 + shift as u16 > bpp {
+                return None;
+            }
+            i += 1;
+        }
+        Some(Self {
+            bpp,
+            red,
+            green,
+            blue,
+        })
+    }
+
+    /// The pixel format of a video mode. Returns `None` if the mode is not
+    /// [`MemoryModel::RGB`] or is not supported by [`PixelFormat::new`].
+    pub fn from_mode(mode: &VideoMode) -> Option<Self> {
+        if mode.memory_model != MemoryModel::RGB {
+            return None;
+        }
+        Self::new(
+            mode.bpp,
+            (mode.red_mask_size, mode.red_mask_shift),
+            (mode.green_mask_size, mode.green_mask_shift),
+            (mode.blue_mask_size, mode.blue_mask_shift),
+        )
+    }
+
+    /// The number of bytes per pixel.
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.bpp as usize / 8
+    }
+
+    /// Pack a colour into a pixel value, scaling each channel to the size of
+    /// its mask.
+    pub fn pack(&self, color: Rgb) -> u32 {
+        fn channel(value: u8, (size, shift): (u8, u8)) -> u32 {
+            let value = if size <= 8 {
+                value as u32 >> (8 - size)
+            } else {
+                (value as u32) << (size - 8)
+            };
+            value << shift
+        }
+        channel(color.r, self.red) | channel(color.g, self.green) | channel(color.b, self.blue)
+    }
+
+    /// Unpack a pixel value into a colour. This is the inverse of
+    /// [`pack`](Self::pack), up to the precision of the masks.
+    pub fn unpack(&self, pixel: u32) -> Rgb {
+        fn channel(pixel: u32, (size, shift): (u8, u8)) -> u8 {
+            let value = (pixel >> shift) & ((1 << size) - 1);
+            if size <= 8 {
+                (value << (8 - size)) as u8
+            } else {
+                (value >> (size - 8)) as u8
+            }
+        }
+        Rgb::new(
+            channel(pixel, self.red),
+            channel(pixel, self.green),
+            channel(pixel, self.blue),
+        )
+    }
+}
+
+/// A drawing surface over framebuffer memory.
+///
+/// All drawing is clipped to the canvas, so coordinates outside of it are
+/// ignored rather than written out of bounds.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "mock")] {
+/// use limine::framebuffer::Rgb;
+/// # let response = limine::mock::framebuffers(&[limine::mock::Framebuffer::new(64, 48)]);
+///
+/// let framebuffer = response.framebuffers().next().unwrap();
+/// // SAFETY: nothing else is accessing the framebuffer
+/// let mut canvas = unsafe { framebuffer.canvas() }.unwrap();
+///
+/// canvas.fill_rect(0, 0, 64, 48, Rgb::BLACK);
+/// canvas.write_pixel(3, 4, Rgb::new(0xff, 0x80, 0));
+/// assert_eq!(canvas.read_pixel(3, 4), Some(Rgb::new(0xff, 0x80, 0)));
+/// assert_eq!(canvas.read_pixel(64, 0), None);
+///
+/// canvas.scroll(4, Rgb::WHITE);
+/// assert_eq!(canvas.read_pixel(3, 0), Some(Rgb::new(0xff, 0x80, 0)));
+/// assert_eq!(canvas.read_pixel(0, 47), Some(Rgb::WHITE));
+/// # }
+/// ```
+pub struct Canvas<'a> {
+    addr: NonNull<u8>,
+    width: usize,
+    height: usize,
+    pitch: usize,
+    format: PixelFormat,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+unsafe impl Send for Canvas<'_> {}
+unsafe impl Sync for Canvas<'_> {}
+impl<'a> Canvas<'a> {
+    /// Create a canvas over arbitrary pixel memory, such as a back buffer.
+    /// Returns `None` if `addr` is null or a row does not fit in `pitch`.
+    ///
+    /// # Safety
+    /// `addr` must be valid for reads and writes of `pitch * height` bytes for
+    /// `'a`, and nothing else may access that memory during `'a`.
+    pub unsafe fn from_raw_parts(
+        addr: *mut u8,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        format: PixelFormat,
+    ) -> Option<Self> {
+        if width.checked_mul(format.bytes_per_pixel())? > pitch {
+            return None;
+        }
+        Some(Self {
+            addr: NonNull::new(addr)?,
+            width,
+            height,
+            pitch,
+            format,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The width of the canvas, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// The height of the canvas, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// The pixel format of the canvas.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    fn pixel_ptr(&self, x: usize, y: usize) -> *mut u8 {
+        debug_assert!(x < self.width && y < self.height);
+        unsafe {
+            self.addr
+                .as_ptr()
+                .add(y * self.pitch + x * self.format.bytes_per_pixel())
+        }
+    }
+
+    fn write_raw(&mut self, x: usize, y: usize, pixel: u32) {
+        let ptr = self.pixel_ptr(x, y);
+        let bytes = pixel.to_le_bytes();
+        unsafe {
+            match self.format.bpp {
+                32 => ptr.cast::<u32>().write_unaligned(pixel),
+                16 => ptr.cast::<[u8; 2]>().write_unaligned([bytes[0], bytes[1]]),
+                _ => ptr
+                    .cast::<[u8; 3]>()
+                    .write_unaligned([bytes[0], bytes[1], bytes[2]]),
+            }
+        }
+    }
+
+    /// Clip a rectangle to the canvas, returning its end coordinates.
+    fn clip(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        (
+            x.saturating_add(width).min(self.width),
+            y.saturating_add(height).min(self.height),
+        )
+    }
+
+    /// Read the colour of a pixel, or `None` if it is outside of the canvas.
+    pub fn read_pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let mut bytes = [0; 4];
+        let len = self.format.bytes_per_pixel();
+        unsafe { core::ptr::copy_nonoverlapping(self.pixel_ptr(x, y), bytes.as_mut_ptr(), len) };
+        Some(self.format.unpack(u32::from_le_bytes(bytes)))
+    }
+
+    /// Set the colour of a pixel. Pixels outside of the canvas are ignored.
+    pub fn write_pixel(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.write_raw(x, y, self.format.pack(color));
+        }
+    }
+
+    /// Fill a rectangle with a colour.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        let pixel = self.format.pack(color);
+        let (x_end, y_end) = self.clip(x, y, width, height);
+        for y in y..y_end {
+            for x in x..x_end {
+                self.write_raw(x, y, pixel);
+            }
+        }
+    }
+
+    /// Copy a rectangle of pixels onto the canvas at `(x, y)`. `pixels` is read
+    /// in rows of `width` pixels; a trailing partial row is ignored.
+    pub fn blit(&mut self, x: usize, y: usize, width: usize, pixels: &[Rgb]) {
+        if width == 0 {
+            return;
+        }
+        let (x_end, y_end) = self.clip(x, y, width, pixels.len() / width);
+        for (row, dst_y) in pixels.chunks_exact(width).zip(y..y_end) {
+            for (&color, dst_x) in row.iter().zip(x..x_end) {
+                self.write_raw(dst_x, dst_y, self.format.pack(color));
+            }
+        }
+    }
+
+    /// Move the contents of the canvas up by `lines` rows, filling the rows
+    /// exposed at the bottom with a colour.
+    pub fn scroll(&mut self, lines: usize, fill: Rgb) {
+        let lines = lines.min(self.height);
+        let kept = self.height - lines;
+        if kept > 0 {
+            // Row padding is copied too, which keeps this a single move.
+            let len = (kept - 1) * self.pitch + self.width * self.format.bytes_per_pixel();
+            unsafe {
+                let dst = self.addr.as_ptr();
+                core::ptr::copy(dst.add(lines * self.pitch), dst, len);
+            }
+        }
+        self.fill_rect(0, kept, self.width, lines, fill);
+    }
+}
+
+crate::layout::check_layout!(framebuffer);
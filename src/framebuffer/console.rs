@@ -0,0 +1,384 @@
+//! A text console which draws on a [`Canvas`] with a bitmap font.
+//!
+//! The built-in [`Font::DEFAULT`] is the 8x13 "fixed" font from the X.Org
+//! misc-fixed collection, which is in the public domain, covering
+//! ISO-8859-1. Other fonts can be loaded from PSF2 files with
+//! [`Font::from_psf2`].
+//!
+//! The console understands `\n`, `\r`, `\t` and backspace, wraps long lines
+//! and scrolls when it reaches the bottom. It also handles a subset of ANSI
+//! escape sequences: `ESC[...m` for the 16 standard colours, bold (drawn as
+//! the bright colours) and resets, `ESC[2J` to clear the screen, and
+//! `ESC[row;colH` to move the cursor.
+
+use core::fmt;
+
+use super::{Canvas, Framebuffer, Rgb};
+
+/// A monochrome bitmap font with one glyph per character.
+#[derive(Clone, Copy)]
+pub struct Font {
+    width: usize,
+    height: usize,
+    glyph_size: usize,
+    glyph_count: usize,
+    glyphs: &'static [u8],
+}
+impl Font {
+    /// The built-in 8x13 font.
+    pub const DEFAULT: Self = match Self::from_psf2(include_bytes!("font-8x13.psf")) {
+        Some(font) => font,
+        None => panic!("invalid built-in font"),
+    };
+
+    /// Load a font from the contents of a PSF2 file. Glyphs are looked up by
+    /// code point, and any Unicode table in the file is ignored. Returns
+    /// `None` if the file is malformed or truncated.
+    pub const fn from_psf2(data: &'static [u8]) -> Option<Self> {
+        const fn read_u32(data: &[u8], offset: usize) -> u32 {
+            u32::from_le_bytes([
+                data[offset],
+                data[offset + 1],
+                data[offset + 2],
+                data[offset + 3],
+            ])
+        }
+
+        if data.len() < 32 || read_u32(data, 0) != 0x864a_b572 {
+            return None;
+        }
+        let header_size = read_u32(data, 8) as usize;
+        let glyph_count = read_u32(data, 16) as usize;
+        let glyph_size = read_u32(data, 20) as usize;
+        let height = read_u32(data, 24) as usize;
+        let width = read_u32(data, 28) as usize;
+
+        if width == 0 || height == 0 || glyph_count == 0 {
+            return None;
+        }
+        if glyph_size != height * width.div_ceil(8) {
+            return None;
+        }
+        match glyph_count.checked_mul(glyph_size) {
+            Some(size) if header_size <= data.len() && size <= data.len() - header_size => {}
+            _ => return None,
+        }
+        Some(Self {
+            width,
+            height,
+            glyph_size,
+            glyph_count,
+            glyphs: data.split_at(header_size).1,
+        })
+    }
+
+    /// The width of a glyph, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// The height of a glyph, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the bitmap of a character, one row after another with each row
+    /// padded to a whole byte. Characters without a glyph are drawn as `?`.
+    pub fn glyph(&self, c: char) -> &'static [u8] {
+        let index = match c as usize {
+            index if index < self.glyph_count => index,
+            _ if ('?' as usize) < self.glyph_count => '?' as usize,
+            _ => 0,
+        };
+        let start = index * self.glyph_size;
+        &self.glyphs[start..start + self.glyph_size]
+    }
+}
+
+/// The 16 standard ANSI colours, normal then bright.
+const PALETTE: [Rgb; 16] = [
+    Rgb::new(0x00, 0x00, 0x00),
+    Rgb::new(0xaa, 0x00, 0x00),
+    Rgb::new(0x00, 0xaa, 0x00),
+    Rgb::new(0xaa, 0x55, 0x00),
+    Rgb::new(0x00, 0x00, 0xaa),
+    Rgb::new(0xaa, 0x00, 0xaa),
+    Rgb::new(0x00, 0xaa, 0xaa),
+    Rgb::new(0xaa, 0xaa, 0xaa),
+    Rgb::new(0x55, 0x55, 0x55),
+    Rgb::new(0xff, 0x55, 0x55),
+    Rgb::new(0x55, 0xff, 0x55),
+    Rgb::new(0xff, 0xff, 0x55),
+    Rgb::new(0x55, 0x55, 0xff),
+    Rgb::new(0xff, 0x55, 0xff),
+    Rgb::new(0x55, 0xff, 0xff),
+    Rgb::new(0xff, 0xff, 0xff),
+];
+
+const MAX_PARAMS: usize = 4;
+
+#[derive(Clone, Copy)]
+enum State {
+    Normal,
+    Escape,
+    Csi {
+        params: [u16; MAX_PARAMS],
+        len: usize,
+    },
+}
+
+/// A text console.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "mock")] {
+/// use core::fmt::Write;
+///
+/// use limine::framebuffer::console::Console;
+/// # let response = limine::mock::framebuffers(&[limine::mock::Framebuffer::new(64, 39)]);
+///
+/// let framebuffer = response.framebuffers().next().unwrap();
+/// // SAFETY: nothing else is accessing the framebuffer
+/// let mut console = unsafe { Console::from_framebuffer(&framebuffer) }.unwrap();
+/// assert_eq!(console.size(), (8, 3));
+///
+/// writeln!(console, "\x1b[31mHello,\x1b[0m world!").unwrap();
+/// assert_eq!(console.cursor(), (0, 2));
+///
+/// // Writing past the bottom scrolls the console
+/// write!(console, "one\ntwo\nthree").unwrap();
+/// assert_eq!(console.cursor(), (5, 2));
+/// # }
+/// ```
+pub struct Console<'a> {
+    canvas: Canvas<'a>,
+    font: Font,
+    columns: usize,
+    rows: usize,
+    column: usize,
+    row: usize,
+    default_fg: Rgb,
+    default_bg: Rgb,
+    fg: Rgb,
+    bg: Rgb,
+    bold: bool,
+    state: State,
+}
+impl<'a> Console<'a> {
+    /// Create a console drawing on `canvas` with the [default
+    /// font](Font::DEFAULT), in light grey on black. The canvas is cleared.
+    pub fn new(canvas: Canvas<'a>) -> Self {
+        let mut console = Self {
+            canvas,
+            font: Font::DEFAULT,
+            columns: 0,
+            rows: 0,
+            column: 0,
+            row: 0,
+            default_fg: PALETTE[7],
+            default_bg: PALETTE[0],
+            fg: PALETTE[7],
+            bg: PALETTE[0],
+            bold: false,
+            state: State::Normal,
+        };
+        console.resize();
+        console.clear();
+        console
+    }
+
+    /// Create a console drawing on a framebuffer. See [`Framebuffer::canvas`].
+    ///
+    /// # Safety
+    /// See [`Framebuffer::canvas`].
+    pub unsafe fn from_framebuffer(framebuffer: &Framebuffer<'a>) -> Option<Self> {
+        framebuffer.canvas().map(Self::new)
+    }
+
+    /// Use a different font. The console is cleared.
+    pub fn with_font(mut self, font: Font) -> Self {
+        self.font = font;
+        self.resize();
+        self.clear();
+        self
+    }
+
+    /// Use different default colours, which are restored by `ESC[0m`. The
+    /// console is cleared.
+    pub fn with_colors(mut self, fg: Rgb, bg: Rgb) -> Self {
+        (self.default_fg, self.default_bg) = (fg, bg);
+        self.reset_colors();
+        self.clear();
+        self
+    }
+
+    /// The size of the console, as `(columns, rows)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// The position of the cursor, as `(column, row)`.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.column, self.row)
+    }
+
+    /// Clear the console with the current background colour and move the
+    /// cursor to the top left.
+    pub fn clear(&mut self) {
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        self.canvas.fill_rect(0, 0, width, height, self.bg);
+        (self.column, self.row) = (0, 0);
+    }
+
+    /// Write a single character, interpreting control characters and escape
+    /// sequences.
+    pub fn write_char(&mut self, c: char) {
+        match (self.state, c) {
+            (State::Normal, '\x1b') => self.state = State::Escape,
+            (State::Normal, c) => self.put_char(c),
+            (State::Escape, '[') => {
+                self.state = State::Csi {
+                    params: [0; MAX_PARAMS],
+                    len: 0,
+                }
+            }
+            (State::Escape, _) => self.state = State::Normal,
+            (State::Csi { mut params, len }, '0'..='9') => {
+                let digit = c as u16 - '0' as u16;
+                let index = len.min(MAX_PARAMS - 1);
+                params[index] = params[index].saturating_mul(10).saturating_add(digit);
+                self.state = State::Csi {
+                    params,
+                    len: len.max(1),
+                };
+            }
+            (State::Csi { params, len }, ';') => {
+                self.state = State::Csi {
+                    params,
+                    len: (len.max(1) + 1).min(MAX_PARAMS),
+                }
+            }
+            (State::Csi { params, len }, c) => {
+                self.state = State::Normal;
+                self.control_sequence(&params[..len], c);
+            }
+        }
+    }
+
+    fn resize(&mut self) {
+        self.columns = self.canvas.width() / self.font.width;
+        self.rows = self.canvas.height() / self.font.height;
+        self.column = self.column.min(self.columns.saturating_sub(1));
+        self.row = self.row.min(self.rows.saturating_sub(1));
+    }
+
+    fn reset_colors(&mut self) {
+        (self.fg, self.bg, self.bold) = (self.default_fg, self.default_bg, false);
+    }
+
+    fn put_char(&mut self, c: char) {
+        if self.columns == 0 || self.rows == 0 {
+            return;
+        }
+        match c {
+            '\n' => self.new_line(),
+            '\r' => self.column = 0,
+            '\t' => {
+                let next = (self.column / 8 + 1) * 8;
+                if next >= self.columns {
+                    self.new_line();
+                } else {
+                    self.column = next;
+                }
+            }
+            '\x08' => self.column = self.column.saturating_sub(1),
+            c if c.is_control() => {}
+            c => {
+                if self.column >= self.columns {
+                    self.new_line();
+                }
+                self.draw_glyph(c);
+                self.column += 1;
+            }
+        }
+    }
+
+    fn new_line(&mut self) {
+        self.column = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+        } else {
+            self.canvas.scroll(self.font.height, self.bg);
+        }
+    }
+
+    fn draw_glyph(&mut self, c: char) {
+        let (fg, bg) = (
+            self.canvas.format.pack(self.fg),
+            self.canvas.format.pack(self.bg),
+        );
+        let (x, y) = (self.column * self.font.width, self.row * self.font.height);
+        let stride = self.font.width.div_ceil(8);
+        for (dy, row) in self.font.glyph(c).chunks_exact(stride).enumerate() {
+            for dx in 0..self.font.width {
+                let set = row[dx / 8] & (0x80 >> (dx % 8)) != 0;
+                self.canvas
+                    .write_raw(x + dx, y + dy, if set { fg } else { bg });
+            }
+        }
+    }
+
+    fn control_sequence(&mut self, params: &[u16], command: char) {
+        match command {
+            'm' if params.is_empty() => self.reset_colors(),
+            'm' => {
+                for &param in params {
+                    self.select_graphic_rendition(param);
+                }
+            }
+            'J' if params.first() == Some(&2) => {
+                let (column, row) = (self.column, self.row);
+                self.clear();
+                (self.column, self.row) = (column, row);
+            }
+            'H' => {
+                let row = params.first().map_or(1, |&row| row.max(1)) as usize;
+                let column = params.get(1).map_or(1, |&column| column.max(1)) as usize;
+                self.row = (row - 1).min(self.rows.saturating_sub(1));
+                self.column = (column - 1).min(self.columns.saturating_sub(1));
+            }
+            _ => {}
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, param: u16) {
+        let bright = if self.bold { 8 } else { 0 };
+        match param {
+            0 => self.reset_colors(),
+            1 => {
+                self.bold = true;
+                if let Some(index) = PALETTE[..8].iter().position(|&c| c == self.fg) {
+                    self.fg = PALETTE[index + 8];
+                }
+            }
+            22 => self.bold = false,
+            30..=37 => self.fg = PALETTE[(param - 30) as usize + bright],
+            39 => self.fg = self.default_fg,
+            40..=47 => self.bg = PALETTE[(param - 40) as usize],
+            49 => self.bg = self.default_bg,
+            90..=97 => self.fg = PALETTE[(param - 90) as usize + 8],
+            100..=107 => self.bg = PALETTE[(param - 100) as usize + 8],
+            _ => {}
+        }
+    }
+}
+impl fmt::Write for Console<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().for_each(|c| self.write_char(c));
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        Console::write_char(self, c);
+        Ok(())
+    }
+}
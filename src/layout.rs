@@ -0,0 +1,265 @@
+//! Compile-time checks that the protocol structures match the layout of their
+//! C definitions in `limine.h`.
+//!
+//! Each entry gives the size of a structure and the offset of each of its
+//! fields, and a mismatch fails the build. The table is written by hand from
+//! `limine.h`, so changes to the protocol must be copied into it.
+//!
+//! The table is kept here in one place, grouped by the module defining each
+//! structure. Each module expands its own group with [`check_layout!`], so
+//! that private fields can be checked without widening their visibility.
+
+macro_rules! layout {
+    ($(
+        $(#[$attr:meta])*
+        $ty:ty: $size:literal {
+            $($field:ident: $offset:literal),* $(,)?
+        }
+    )*) => {$(
+        $(#[$attr])*
+        const _: () = {
+            assert!(
+                ::core::mem::size_of::<$ty>() == $size,
+                concat!("wrong size for `", stringify!($ty), "`"),
+            );
+            $(assert!(
+                ::core::mem::offset_of!($ty, $field) == $offset,
+                concat!("wrong offset for `", stringify!($ty), "::", stringify!($field), "`"),
+            );)*
+        };
+    )*};
+}
+pub(crate) use layout;
+
+/// Check the layout of the structures defined in the calling module, named as
+/// they are in that module.
+macro_rules! check_layout {
+    (lib) => {
+        $crate::layout::layout! {
+            // LIMINE_BASE_REVISION
+            BaseRevision: 24 {}
+        }
+    };
+    (file) => {
+        $crate::layout::layout! {
+            // struct limine_file
+            File: 112 {
+                revision: 0,
+                addr: 8,
+                size: 16,
+                path: 24,
+                string: 32,
+                media_type: 40,
+                _unused: 44,
+                tftp_ip: 48,
+                tftp_port: 52,
+                partition_idx: 56,
+                mbr_disk_id: 60,
+                gpt_disk_id: 64,
+                gpt_partition_id: 80,
+                partition_uuid: 96,
+            }
+        }
+    };
+    (framebuffer) => {
+        $crate::layout::layout! {
+            // struct limine_framebuffer, revision 0 and 1
+            RawFramebufferV0: 64 {
+                addr: 0,
+                width: 8,
+                height: 16,
+                pitch: 24,
+                bpp: 32,
+                memory_model: 34,
+                red_mask_size: 35,
+                red_mask_shift: 36,
+                green_mask_size: 37,
+                green_mask_shift: 38,
+                blue_mask_size: 39,
+                blue_mask_shift: 40,
+                _unused: 41,
+                edid_size: 48,
+                edid: 56,
+            }
+            RawFramebufferV1: 80 {
+                _v0: 0,
+                mode_ct: 64,
+                modes: 72,
+            }
+            RawFramebuffer: 80 {
+                v0: 0,
+                v1: 0,
+            }
+
+            // struct limine_video_mode
+            VideoMode: 40 {
+                pitch: 0,
+                width: 8,
+                height: 16,
+                bpp: 24,
+                memory_model: 26,
+                red_mask_size: 27,
+                red_mask_shift: 28,
+                green_mask_size: 29,
+                green_mask_shift: 30,
+                blue_mask_size: 31,
+                blue_mask_shift: 32,
+            }
+        }
+    };
+    (memory_map) => {
+        $crate::layout::layout! {
+            // struct limine_memmap_entry
+            Entry: 24 {
+                base: 0,
+                length: 8,
+                entry_type: 16,
+            }
+        }
+    };
+    (modules) => {
+        $crate::layout::layout! {
+            // struct limine_internal_module
+            InternalModule: 24 {
+                path: 0,
+                cmdline: 8,
+                flags: 16,
+            }
+        }
+    };
+    (mp) => {
+        $crate::layout::layout! {
+            // struct limine_mp_info
+            #[cfg(target_arch = "x86_64")]
+            Cpu: 32 {
+                id: 0,
+                lapic_id: 4,
+                _reserved: 8,
+                goto_address: 16,
+                extra: 24,
+            }
+            #[cfg(target_arch = "aarch64")]
+            Cpu: 40 {
+                id: 0,
+                _reserved1: 4,
+                mpidr: 8,
+                _reserved: 16,
+                goto_address: 24,
+                extra: 32,
+            }
+            #[cfg(target_arch = "riscv64")]
+            Cpu: 40 {
+                id: 0,
+                hartid: 8,
+                _reserved: 16,
+                goto_address: 24,
+                extra: 32,
+            }
+            #[cfg(target_arch = "loongarch64")]
+            Cpu: 8 {
+                _reserved: 0,
+            }
+        }
+    };
+    (request) => {
+        $crate::layout::layout! {
+            // LIMINE_REQUESTS_START_MARKER and LIMINE_REQUESTS_END_MARKER
+            RequestsStartMarker: 32 {}
+            RequestsEndMarker: 16 {}
+
+            // Requests
+            BootloaderInfoRequest: 48 { id: 0, revision: 32, response: 40 }
+            FirmwareTypeRequest: 48 { id: 0, revision: 32, response: 40 }
+            StackSizeRequest: 56 { id: 0, revision: 32, response: 40, size: 48 }
+            HhdmRequest: 48 { id: 0, revision: 32, response: 40 }
+            FramebufferRequest: 48 { id: 0, revision: 32, response: 40 }
+            PagingModeRequest: 72 {
+                id: 0,
+                revision: 32,
+                response: 40,
+                mode: 48,
+                max_mode: 56,
+                min_mode: 64,
+            }
+            MpRequest: 56 { id: 0, revision: 32, response: 40, flags: 48 }
+            MemoryMapRequest: 48 { id: 0, revision: 32, response: 40 }
+            EntryPointRequest: 56 { id: 0, revision: 32, response: 40, entry_point: 48 }
+            ExecutableFileRequest: 48 { id: 0, revision: 32, response: 40 }
+            ModuleRequest: 64 {
+                id: 0,
+                revision: 32,
+                response: 40,
+                internal_module_ct: 48,
+                internal_modules: 56,
+            }
+            RsdpRequest: 48 { id: 0, revision: 32, response: 40 }
+            SmbiosRequest: 48 { id: 0, revision: 32, response: 40 }
+            EfiSystemTableRequest: 48 { id: 0, revision: 32, response: 40 }
+            EfiMemoryMapRequest: 48 { id: 0, revision: 32, response: 40 }
+            DateAtBootRequest: 48 { id: 0, revision: 32, response: 40 }
+            ExecutableAddressRequest: 48 { id: 0, revision: 32, response: 40 }
+            ExecutableCmdlineRequest: 48 { id: 0, revision: 32, response: 40 }
+            DeviceTreeBlobRequest: 48 { id: 0, revision: 32, response: 40 }
+            #[cfg(target_arch = "riscv64")]
+            BspHartidRequest: 48 { id: 0, revision: 32, response: 40 }
+        }
+    };
+    (response) => {
+        $crate::layout::layout! {
+            // Responses
+            BootloaderInfoResponse: 24 { revision: 0, name: 8, version: 16 }
+            FirmwareTypeResponse: 16 { revision: 0, firmware_type: 8 }
+            StackSizeResponse: 8 { revision: 0 }
+            HhdmResponse: 16 { revision: 0, offset: 8 }
+            FramebufferResponse: 24 { revision: 0, framebuffer_ct: 8, framebuffers: 16 }
+            PagingModeResponse: 16 { revision: 0, mode: 8 }
+            #[cfg(target_arch = "x86_64")]
+            MpResponse: 32 {
+                revision: 0,
+                flags: 8,
+                bsp_lapic_id: 12,
+                cpu_ct: 16,
+                cpus: 24,
+            }
+            #[cfg(target_arch = "aarch64")]
+            MpResponse: 40 {
+                revision: 0,
+                flags: 8,
+                bsp_mpidr: 16,
+                cpu_ct: 24,
+                cpus: 32,
+            }
+            #[cfg(target_arch = "riscv64")]
+            MpResponse: 40 {
+                revision: 0,
+                flags: 8,
+                bsp_hartid: 16,
+                cpu_ct: 24,
+                cpus: 32,
+            }
+            #[cfg(target_arch = "loongarch64")]
+            MpResponse: 16 { cpu_ct: 0, cpus: 8 }
+            MemoryMapResponse: 24 { revision: 0, entry_ct: 8, entries: 16 }
+            EntryPointResponse: 8 { revision: 0 }
+            ExecutableFileResponse: 16 { revision: 0, file: 8 }
+            ModuleResponse: 24 { revision: 0, module_ct: 8, modules: 16 }
+            RsdpResponse: 16 { revision: 0, address: 8 }
+            SmbiosResponse: 24 { revision: 0, entry_32: 8, entry_64: 16 }
+            EfiSystemTableResponse: 16 { revision: 0, address: 8 }
+            EfiMemoryMapResponse: 40 {
+                revision: 0,
+                memmap: 8,
+                memmap_size: 16,
+                desc_size: 24,
+                desc_version: 32,
+            }
+            DateAtBootResponse: 16 { revision: 0, timestamp: 8 }
+            ExecutableAddressResponse: 24 { revision: 0, physical_base: 8, virtual_base: 16 }
+            ExecutableCmdlineResponse: 16 { revision: 0, cmdline: 8 }
+            DeviceTreeBlobResponse: 16 { revision: 0, dtb_ptr: 8 }
+            #[cfg(target_arch = "riscv64")]
+            BspHartidResponse: 16 { revision: 0, bsp_hartid: 8 }
+        }
+    };
+}
+pub(crate) use check_layout;
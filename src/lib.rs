@@ -13,6 +13,18 @@
 //! - `ipaddr`: Enables functions in [`file::File`] to return `Ipv4Addr`. This
 //!   is feature gated because it will only appear in stable on Rust 1.77.0, on
 //!   March 21st.
+//! - `alloc`: Enables the [`boot_info`] module, which copies responses onto the
+//!   heap so that bootloader-reclaimable memory can be reused, and
+//!   `mp::Launcher`, which starts application processors with Rust closures.
+//! - `mock`: Enables the [`mock`] module, which builds responses on the host so
+//!   that code reading requests can be unit tested. This links against `std`,
+//!   so it has no effect on `target_os = "none"` targets.
+//! - `scan`: Enables the [`scan`] module, which lists the requests contained
+//!   in a compiled executable. The `limine-scan` tool wraps it for the host.
+//! - `frame_alloc`: Enables the [`frame_alloc`] module, which provides physical
+//!   frame allocators seeded from the memory map.
+//! - `demangle`: Demangles Rust symbol names when displaying a
+//!   [`backtrace::Symbol`].
 //!
 //! # Revisions
 //! Many types in the limine boot protocol have associated revisions. These
@@ -58,15 +70,43 @@
 
 use core::cell::UnsafeCell;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(all(feature = "mock", not(target_os = "none")))]
+extern crate std;
+
+mod error;
+pub use error::Error;
+mod layout;
+mod phys;
+
+pub mod acpi;
+pub mod addr;
+pub mod backtrace;
+#[cfg(feature = "alloc")]
+pub mod boot_info;
+pub mod cmdline;
+pub mod edid;
+pub mod efi;
+pub mod elf;
+pub mod fdt;
 pub mod file;
 pub mod firmware_type;
+#[cfg(feature = "frame_alloc")]
+pub mod frame_alloc;
 pub mod framebuffer;
 pub mod memory_map;
+#[cfg(all(feature = "mock", not(target_os = "none")))]
+pub mod mock;
 pub mod modules;
 pub mod mp;
 pub mod paging;
+pub mod percpu;
 pub mod request;
 pub mod response;
+#[cfg(feature = "scan")]
+pub mod scan;
+pub mod smbios;
 pub mod smp;
 
 /// A tag setting the base revision supported by the executable. Set this in your
@@ -121,6 +161,7 @@ impl BaseRevision {
 }
 unsafe impl Sync for BaseRevision {}
 unsafe impl Send for BaseRevision {}
+layout::check_layout!(lib);
 
 #[cfg(not(any(
     target_arch = "x86_64",
@@ -1,5 +1,10 @@
 //! Auxiliary types for the [memory map request](crate::request::MemoryMapRequest)
 
+use crate::{addr::PhysAddr, Error};
+
+/// The size of a page, to which usable entries are aligned by [`normalize`].
+pub const PAGE_SIZE: u64 = 0x1000;
+
 /// A type of entry within the memory map.
 #[repr(transparent)]
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -50,3 +55,189 @@ pub struct Entry {
     /// The type of the memory region. See [`EntryType`] for specific values.
     pub entry_type: EntryType,
 }
+impl Entry {
+    /// The end of the memory region (exclusive), in *physical space*. Saturates
+    /// at `u64::MAX` if the entry is malformed.
+    pub fn end(&self) -> u64 {
+        self.base.saturating_add(self.length)
+    }
+
+    /// The base of the memory region as a [`PhysAddr`].
+    pub fn base_addr(&self) -> PhysAddr {
+        PhysAddr::new(self.base)
+    }
+
+    /// The end of the memory region (exclusive) as a [`PhysAddr`]. See
+    /// [`end`](Self::end).
+    pub fn end_addr(&self) -> PhysAddr {
+        PhysAddr::new(self.end())
+    }
+
+    /// Returns whether `addr` lies within the memory region.
+    pub fn contains(&self, addr: PhysAddr) -> bool {
+        self.base <= addr.as_u64() && addr.as_u64() < self.end()
+    }
+}
+
+/// Two memory map entries of different types which overlap.
+#[derive(Clone, Copy)]
+pub struct Overlap {
+    /// The entry with the lower base address.
+    pub first: Entry,
+    /// The entry with the higher base address.
+    pub second: Entry,
+}
+
+/// A normalized memory map, as returned by [`normalize`].
+pub struct Normalized<'a> {
+    entries: &'a mut [Entry],
+}
+impl<'a> Normalized<'a> {
+    /// Returns the normalized entries.
+    pub fn entries(&self) -> &[Entry] {
+        self.entries
+    }
+
+    /// Returns the normalized entries, consuming the wrapper.
+    pub fn into_entries(self) -> &'a mut [Entry] {
+        self.entries
+    }
+
+    /// Returns every pair of entries of different types that overlap. Limine
+    /// guarantees that usable and bootloader-reclaimable entries never
+    /// overlap, but other types have no such guarantee.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limine::memory_map::{normalize, Entry, EntryType};
+    ///
+    /// let entry = |base, end, entry_type| Entry { base, length: end - base, entry_type };
+    /// let pairs = |raw: &[Entry]| {
+    ///     let refs: Vec<&Entry> = raw.iter().collect();
+    ///     let mut buf = raw.to_vec();
+    ///     let normalized = normalize(&refs, &mut buf).unwrap();
+    ///     normalized
+    ///         .overlaps()
+    ///         .map(|overlap| (overlap.first.base, overlap.second.base))
+    ///         .collect::<Vec<_>>()
+    /// };
+    ///
+    /// // Entries nested in the same enclosing entry also overlap each other
+    /// let raw = [
+    ///     entry(0, 100, EntryType::RESERVED),
+    ///     entry(10, 20, EntryType::ACPI_NVS),
+    ///     entry(15, 30, EntryType::BAD_MEMORY),
+    /// ];
+    /// assert_eq!(pairs(&raw), [(0, 10), (0, 15), (10, 15)]);
+    ///
+    /// // Entries of the same type are merged, even with another type between
+    /// // them, so they are not reported
+    /// let raw = [
+    ///     entry(0, 100, EntryType::RESERVED),
+    ///     entry(10, 20, EntryType::ACPI_NVS),
+    ///     entry(50, 60, EntryType::RESERVED),
+    /// ];
+    /// assert_eq!(pairs(&raw), [(0, 10)]);
+    /// let mut buf = raw;
+    /// let refs = [&raw[0], &raw[1], &raw[2]];
+    /// let normalized = normalize(&refs, &mut buf).unwrap();
+    /// let bounds: Vec<_> = normalized.entries().iter().map(|e| (e.base, e.end())).collect();
+    /// assert_eq!(bounds, [(0, 100), (10, 20)]);
+    /// ```
+    pub fn overlaps(&self) -> impl Iterator<Item = Overlap> + '_ {
+        let entries = &*self.entries;
+        entries.iter().enumerate().flat_map(move |(i, &first)| {
+            // Entries are sorted by base, so every entry overlapping `first`
+            // from above comes right after it.
+            entries[i + 1..]
+                .iter()
+                .take_while(move |second| second.base < first.end())
+                .filter(move |second| second.entry_type != first.entry_type)
+                .map(move |&second| Overlap { first, second })
+        })
+    }
+}
+
+/// Normalize a memory map into `buf`:
+/// - [`USABLE`](EntryType::USABLE) entries are shrunk inwards to
+///   [`PAGE_SIZE`] boundaries, and dropped if nothing remains.
+/// - Empty entries are dropped.
+/// - Entries are sorted by base address.
+/// - Adjacent or overlapping entries of the same type are merged.
+///
+/// Overlapping entries of different types are kept as they are, and can be
+/// found with [`Normalized::overlaps`]. An error is returned if `buf` is
+/// shorter than `entries`.
+///
+/// # Examples
+/// ```rust
+/// use limine::memory_map::{normalize, Entry, EntryType};
+///
+/// let raw = [
+///     Entry { base: 0x10_0000, length: 0x8_0000, entry_type: EntryType::USABLE },
+///     Entry { base: 0x800, length: 0x9_f000, entry_type: EntryType::USABLE },
+///     Entry { base: 0x18_0000, length: 0x1000, entry_type: EntryType::USABLE },
+///     Entry { base: 0x18_0800, length: 0x1000, entry_type: EntryType::RESERVED },
+/// ];
+/// let mut buf = [Entry { base: 0, length: 0, entry_type: EntryType::RESERVED }; 4];
+///
+/// let normalized = normalize(&[&raw[0], &raw[1], &raw[2], &raw[3]], &mut buf).unwrap();
+/// let entries = normalized.entries();
+/// assert_eq!(entries.len(), 3);
+/// assert_eq!((entries[0].base, entries[0].end()), (0x1000, 0x9_f000));
+/// assert_eq!((entries[1].base, entries[1].end()), (0x10_0000, 0x18_1000));
+/// assert_eq!(normalized.overlaps().count(), 1);
+/// ```
+pub fn normalize<'a>(entries: &[&Entry], buf: &'a mut [Entry]) -> Result<Normalized<'a>, Error> {
+    let buf = buf.get_mut(..entries.len()).ok_or(Error::BufferTooSmall {
+        needed: entries.len(),
+    })?;
+
+    let mut len = 0;
+    for &&entry in entries {
+        let (base, end) = if entry.entry_type == EntryType::USABLE {
+            (
+                entry.base.saturating_add(PAGE_SIZE - 1) & !(PAGE_SIZE - 1),
+                entry.end() & !(PAGE_SIZE - 1),
+            )
+        } else {
+            (entry.base, entry.end())
+        };
+        if end > base {
+            buf[len] = Entry {
+                base,
+                length: end - base,
+                entry_type: entry.entry_type,
+            };
+            len += 1;
+        }
+    }
+
+    let buf = &mut buf[..len];
+    buf.sort_unstable_by_key(|entry| (entry.base, entry.length));
+
+    let mut len: usize = 0;
+    for i in 0..buf.len() {
+        let entry = buf[i];
+        // Merged entries of one type never touch, so only the last one of
+        // this type can reach `entry`, even with other types in between.
+        let open = buf[..len]
+            .iter()
+            .rposition(|e| e.entry_type == entry.entry_type);
+        match open.map(|open| &mut buf[open]) {
+            Some(open) if open.end() >= entry.base => {
+                open.length = open.end().max(entry.end()) - open.base;
+            }
+            _ => {
+                buf[len] = entry;
+                len += 1;
+            }
+        }
+    }
+
+    Ok(Normalized {
+        entries: &mut buf[..len],
+    })
+}
+
+crate::layout::check_layout!(memory_map);
@@ -0,0 +1,576 @@
+//! A host-side stand-in for the bootloader, for use in unit tests.
+//!
+//! Every function in this module builds an owned, correctly laid-out response
+//! and leaks it, so that it can be handed to `set_response` on any request
+//! (for example [`HhdmRequest::set_response`](crate::request::HhdmRequest::set_response)).
+//! Code under test can then call `get_response` exactly as it would after a
+//! real boot.
+//!
+//! This module is only available with the `mock` feature, which also links
+//! against `std`, and is left out on targets without an operating system.
+//!
+//! # Examples
+//! ```rust
+//! use limine::{
+//!     memory_map::{Entry, EntryType},
+//!     mock,
+//!     request::{HhdmRequest, MemoryMapRequest},
+//! };
+//!
+//! static HHDM_REQUEST: HhdmRequest = HhdmRequest::new();
+//! static MEMORY_MAP_REQUEST: MemoryMapRequest = MemoryMapRequest::new();
+//!
+//! unsafe {
+//!     HHDM_REQUEST.set_response(Some(mock::hhdm(0xffff_8000_0000_0000)));
+//!     MEMORY_MAP_REQUEST.set_response(Some(mock::memory_map(&[Entry {
+//!         base: 0x1000,
+//!         length: 0x9f000,
+//!         entry_type: EntryType::USABLE,
+//!     }])));
+//! }
+//!
+//! let hhdm = HHDM_REQUEST.get_response().unwrap();
+//! assert_eq!(hhdm.offset(), 0xffff_8000_0000_0000);
+//!
+//! let memory_map = MEMORY_MAP_REQUEST.get_response().unwrap();
+//! assert_eq!(memory_map.entries().len(), 1);
+//! assert_eq!(memory_map.entries()[0].length, 0x9f000);
+//! ```
+
+use core::{
+    ffi::{c_char, c_void},
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    ptr::NonNull,
+};
+use std::{boxed::Box, ffi::CString, vec::Vec};
+
+use crate::{
+    file::{File as RawFile, MediaType, Uuid},
+    firmware_type::FirmwareType,
+    framebuffer::{MemoryModel, RawFramebuffer, RawFramebufferV0, RawFramebufferV1, VideoMode},
+    memory_map::Entry,
+    mp::{self, Cpu},
+    paging,
+    response::*,
+    BaseRevision,
+};
+
+fn leak<T>(value: T) -> &'static mut T {
+    Box::leak(Box::new(value))
+}
+
+fn leak_slice<T>(values: Vec<T>) -> &'static mut [T] {
+    Box::leak(values.into_boxed_slice())
+}
+
+fn leak_str(s: &str) -> *const c_char {
+    CString::new(s)
+        .expect("mock strings must not contain NUL bytes")
+        .into_raw()
+}
+
+/// Answer a [`BaseRevision`] tag, as the bootloader would. `loaded` is the
+/// revision the bootloader claims to use; the tag is marked as supported if
+/// `loaded` is at least the requested revision.
+///
+/// # Safety
+/// No other thread may access the tag while it is being answered.
+pub unsafe fn answer_base_revision(tag: &BaseRevision, loaded: u64) {
+    let requested = tag.revision.get().read_volatile();
+    if loaded >= requested {
+        tag.revision.get().write_volatile(0);
+    }
+    tag.loaded.get().write_volatile(loaded);
+}
+
+/// Build a [`BootloaderInfoResponse`].
+pub fn bootloader_info(name: &str, version: &str) -> &'static BootloaderInfoResponse {
+    leak(BootloaderInfoResponse {
+        revision: 0,
+        name: leak_str(name),
+        version: leak_str(version),
+    })
+}
+
+/// Build a [`FirmwareTypeResponse`].
+pub fn firmware_type(firmware_type: FirmwareType) -> &'static FirmwareTypeResponse {
+    leak(FirmwareTypeResponse {
+        revision: 0,
+        firmware_type,
+    })
+}
+
+/// Build a [`StackSizeResponse`].
+pub fn stack_size() -> &'static StackSizeResponse {
+    leak(StackSizeResponse { revision: 0 })
+}
+
+/// Build a [`HhdmResponse`] with the given offset.
+pub fn hhdm(offset: u64) -> &'static HhdmResponse {
+    leak(HhdmResponse {
+        revision: 0,
+        offset,
+    })
+}
+
+/// Build a [`PagingModeResponse`].
+pub fn paging_mode(mode: paging::Mode) -> &'static PagingModeResponse {
+    leak(PagingModeResponse { revision: 0, mode })
+}
+
+/// Build a [`MemoryMapResponse`] holding copies of the given entries.
+pub fn memory_map(entries: &[Entry]) -> &'static MemoryMapResponse {
+    let entries = leak_slice(entries.to_vec());
+    let pointers = leak_slice(entries.iter_mut().map(|e| e as *mut Entry).collect());
+    leak(MemoryMapResponse {
+        revision: 0,
+        entry_ct: pointers.len() as u64,
+        entries: pointers.as_mut_ptr(),
+    })
+}
+
+/// Build an [`EntryPointResponse`].
+pub fn entry_point() -> &'static EntryPointResponse {
+    leak(EntryPointResponse { revision: 0 })
+}
+
+/// A file to be returned from [`executable_file`] or [`modules`].
+#[derive(Clone)]
+pub struct File {
+    path: CString,
+    string: CString,
+    data: Vec<u8>,
+    media_type: MediaType,
+    partition_idx: u32,
+    gpt_disk_id: Uuid,
+    gpt_partition_id: Uuid,
+    partition_uuid: Uuid,
+}
+impl File {
+    /// Create a new, empty file with the given path.
+    pub fn new(path: &str) -> Self {
+        const NIL: Uuid = Uuid {
+            a: 0,
+            b: 0,
+            c: 0,
+            d: [0; 8],
+        };
+        Self {
+            path: CString::new(path).expect("mock strings must not contain NUL bytes"),
+            string: CString::default(),
+            data: Vec::new(),
+            media_type: MediaType::GENERIC,
+            partition_idx: 0,
+            gpt_disk_id: NIL,
+            gpt_partition_id: NIL,
+            partition_uuid: NIL,
+        }
+    }
+
+    /// Set the string (command line) associated with the file.
+    pub fn with_string(mut self, string: &str) -> Self {
+        self.string = CString::new(string).expect("mock strings must not contain NUL bytes");
+        self
+    }
+
+    /// Set the contents of the file.
+    pub fn with_data(mut self, data: &[u8]) -> Self {
+        self.data = data.to_vec();
+        self
+    }
+
+    /// Set the media type of the file.
+    pub fn with_media_type(mut self, media_type: MediaType) -> Self {
+        self.media_type = media_type;
+        self
+    }
+
+    /// Set the partition index the file was loaded from. Zero means none.
+    pub fn with_partition_idx(mut self, partition_idx: u32) -> Self {
+        self.partition_idx = partition_idx;
+        self
+    }
+
+    /// Set the GPT disk, GPT partition and partition UUIDs of the file.
+    pub fn with_uuids(
+        mut self,
+        gpt_disk_id: Uuid,
+        gpt_partition_id: Uuid,
+        partition_uuid: Uuid,
+    ) -> Self {
+        self.gpt_disk_id = gpt_disk_id;
+        self.gpt_partition_id = gpt_partition_id;
+        self.partition_uuid = partition_uuid;
+        self
+    }
+
+    fn leak(self) -> &'static mut RawFile {
+        let data: &mut [u8] = leak_slice(self.data);
+        leak(RawFile {
+            revision: 0,
+            addr: data.as_mut_ptr().cast::<c_void>(),
+            size: data.len() as u64,
+            path: self.path.into_raw(),
+            string: self.string.into_raw(),
+            media_type: self.media_type,
+            _unused: MaybeUninit::new(0),
+            tftp_ip: None,
+            tftp_port: None,
+            partition_idx: core::num::NonZeroU32::new(self.partition_idx),
+            mbr_disk_id: None,
+            gpt_disk_id: self.gpt_disk_id,
+            gpt_partition_id: self.gpt_partition_id,
+            partition_uuid: self.partition_uuid,
+        })
+    }
+}
+
+/// Build an [`ExecutableFileResponse`] for the given file.
+pub fn executable_file(file: File) -> &'static ExecutableFileResponse {
+    leak(ExecutableFileResponse {
+        revision: 0,
+        file: file.leak(),
+    })
+}
+
+/// Build a [`ModuleResponse`] holding the given files.
+pub fn modules(files: &[File]) -> &'static ModuleResponse {
+    let pointers = leak_slice(
+        files
+            .iter()
+            .map(|f| f.clone().leak() as *const RawFile)
+            .collect(),
+    );
+    leak(ModuleResponse {
+        revision: 1,
+        module_ct: pointers.len() as u64,
+        modules: pointers.as_ptr(),
+    })
+}
+
+/// A framebuffer to be returned from [`framebuffers`]. The pixel memory is
+/// allocated and zeroed when the response is built.
+#[derive(Clone)]
+pub struct Framebuffer {
+    mode: VideoMode,
+    edid: Option<Vec<u8>>,
+    modes: Vec<VideoMode>,
+}
+impl Framebuffer {
+    /// Create a new 32-bpp RGB framebuffer of the given size, with a tightly
+    /// packed pitch.
+    pub fn new(width: u64, height: u64) -> Self {
+        Self {
+            mode: VideoMode {
+                pitch: width * 4,
+                width,
+                height,
+                bpp: 32,
+                memory_model: MemoryModel::RGB,
+                red_mask_size: 8,
+                red_mask_shift: 16,
+                green_mask_size: 8,
+                green_mask_shift: 8,
+                blue_mask_size: 8,
+                blue_mask_shift: 0,
+            },
+            edid: None,
+            modes: Vec::new(),
+        }
+    }
+
+    /// Replace the current mode of the framebuffer, including its size, pitch
+    /// and pixel format.
+    pub fn with_mode(mut self, mode: VideoMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the EDID blob of the attached display.
+    pub fn with_edid(mut self, edid: &[u8]) -> Self {
+        self.edid = Some(edid.to_vec());
+        self
+    }
+
+    /// Set the video modes reported on response revision 1 and above.
+    pub fn with_modes(mut self, modes: &[VideoMode]) -> Self {
+        self.modes = modes.to_vec();
+        self
+    }
+
+    fn leak(self) -> &'static RawFramebuffer {
+        let mode = self.mode;
+        let pixels: &mut [u8] = leak_slice(std::vec![0u8; (mode.pitch * mode.height) as usize]);
+        let edid = self.edid.map(leak_slice);
+        let modes = leak_slice(self.modes);
+        let mode_ptrs = leak_slice(modes.iter().map(|m| m as *const VideoMode).collect());
+        leak(RawFramebuffer {
+            v1: RawFramebufferV1 {
+                _v0: RawFramebufferV0 {
+                    addr: pixels.as_mut_ptr().cast::<c_void>(),
+                    width: mode.width,
+                    height: mode.height,
+                    pitch: mode.pitch,
+                    bpp: mode.bpp,
+                    memory_model: mode.memory_model,
+                    red_mask_size: mode.red_mask_size,
+                    red_mask_shift: mode.red_mask_shift,
+                    green_mask_size: mode.green_mask_size,
+                    green_mask_shift: mode.green_mask_shift,
+                    blue_mask_size: mode.blue_mask_size,
+                    blue_mask_shift: mode.blue_mask_shift,
+                    _unused: MaybeUninit::new([0; 7]),
+                    edid_size: edid.as_ref().map_or(0, |e| e.len() as u64),
+                    edid: edid.map(|e| NonNull::from(e).cast::<u8>()),
+                },
+                mode_ct: mode_ptrs.len() as u64,
+                modes: mode_ptrs.as_ptr(),
+            },
+        })
+    }
+}
+
+/// Build a [`FramebufferResponse`] holding the given framebuffers. The response
+/// has revision 1, so [`modes`](crate::framebuffer::Framebuffer::modes) is
+/// available.
+pub fn framebuffers(framebuffers: &[Framebuffer]) -> &'static FramebufferResponse {
+    let pointers = leak_slice(
+        framebuffers
+            .iter()
+            .map(|fb| fb.clone().leak() as *const RawFramebuffer)
+            .collect(),
+    );
+    leak(FramebufferResponse {
+        revision: 1,
+        framebuffer_ct: pointers.len() as u64,
+        framebuffers: pointers.as_ptr(),
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+type ArchId = u32;
+#[cfg(not(target_arch = "x86_64"))]
+type ArchId = u64;
+
+/// A multiprocessor topology to be returned from [`Mp::build`].
+///
+/// The architecture-specific ID is the local APIC ID on x86_64, the MPIDR on
+/// aarch64 and the hart ID on riscv64. It is ignored on loongarch64.
+///
+/// # Examples
+/// ```rust
+/// use limine::{mock, request::MpRequest};
+///
+/// static MP_REQUEST: MpRequest = MpRequest::new();
+///
+/// let response = mock::Mp::new(0).with_cpu(0, 0).with_cpu(1, 1).build();
+/// unsafe { MP_REQUEST.set_response(Some(response)) };
+///
+/// assert_eq!(MP_REQUEST.get_response().unwrap().cpus().len(), 2);
+/// ```
+pub struct Mp {
+    #[cfg_attr(target_arch = "loongarch64", allow(dead_code))]
+    bsp: ArchId,
+    cpus: Vec<(u32, ArchId)>,
+}
+impl Mp {
+    /// Create a new topology whose boot processor has the given
+    /// architecture-specific ID. The boot processor is not added to the CPU
+    /// list automatically.
+    pub fn new(bsp: ArchId) -> Self {
+        Self {
+            bsp,
+            cpus: Vec::new(),
+        }
+    }
+
+    /// Add a CPU with the given ACPI processor ID and architecture-specific ID.
+    pub fn with_cpu(mut self, id: u32, arch_id: ArchId) -> Self {
+        self.cpus.push((id, arch_id));
+        self
+    }
+
+    /// Build the [`MpResponse`].
+    pub fn build(self) -> &'static MpResponse {
+        let cpus = leak_slice(
+            self.cpus
+                .into_iter()
+                .map(|(id, arch_id)| new_cpu(id, arch_id))
+                .collect(),
+        );
+        let pointers = leak_slice(cpus.iter_mut().map(|c| c as *mut Cpu).collect());
+        leak(MpResponse {
+            #[cfg(not(target_arch = "loongarch64"))]
+            revision: 0,
+            #[cfg(not(target_arch = "loongarch64"))]
+            flags: mp::ResponseFlags::empty(),
+            #[cfg(target_arch = "x86_64")]
+            bsp_lapic_id: self.bsp,
+            #[cfg(target_arch = "aarch64")]
+            bsp_mpidr: self.bsp,
+            #[cfg(target_arch = "riscv64")]
+            bsp_hartid: self.bsp,
+            cpu_ct: pointers.len() as u64,
+            cpus: pointers.as_mut_ptr(),
+        })
+    }
+}
+
+#[cfg(not(target_arch = "loongarch64"))]
+fn new_goto_address() -> mp::GotoAddress {
+    mp::GotoAddress {
+        inner: core::sync::atomic::AtomicPtr::new(core::ptr::null_mut()),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn new_cpu(id: u32, lapic_id: ArchId) -> Cpu {
+    Cpu {
+        id,
+        lapic_id,
+        _reserved: MaybeUninit::new(0),
+        goto_address: new_goto_address(),
+        extra: core::sync::atomic::AtomicU64::new(0),
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn new_cpu(id: u32, mpidr: ArchId) -> Cpu {
+    Cpu {
+        id,
+        _reserved1: MaybeUninit::new(0),
+        mpidr,
+        _reserved: MaybeUninit::new(0),
+        goto_address: new_goto_address(),
+        extra: core::sync::atomic::AtomicU64::new(0),
+    }
+}
+
+#[cfg(target_arch = "riscv64")]
+fn new_cpu(id: u32, hartid: ArchId) -> Cpu {
+    Cpu {
+        id: id as u64,
+        hartid,
+        _reserved: MaybeUninit::new(0),
+        goto_address: new_goto_address(),
+        extra: core::sync::atomic::AtomicU64::new(0),
+    }
+}
+
+#[cfg(target_arch = "loongarch64")]
+fn new_cpu(_id: u32, _arch_id: ArchId) -> Cpu {
+    Cpu {
+        _reserved: MaybeUninit::new(0),
+    }
+}
+
+/// Run a mock application processor on a new host thread. The thread waits
+/// until the CPU's goto address is written, then jumps to it as the
+/// bootloader would, so code starting APs can be tested on the host.
+#[cfg(not(target_arch = "loongarch64"))]
+pub fn start_ap(cpu: &'static Cpu) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        let goto = cpu
+            .goto_address
+            .inner
+            .load(core::sync::atomic::Ordering::SeqCst);
+        if goto.is_null() {
+            std::thread::yield_now();
+            continue;
+        }
+        let goto: unsafe extern "C" fn(&Cpu) -> ! = unsafe { core::mem::transmute(goto) };
+        unsafe { goto(cpu) }
+    })
+}
+
+/// Build an [`RsdpResponse`] pointing at the given address.
+pub fn rsdp(address: usize) -> &'static RsdpResponse {
+    leak(RsdpResponse {
+        revision: 0,
+        address,
+    })
+}
+
+/// Build a [`SmbiosResponse`] with the given entry point addresses.
+pub fn smbios(
+    entry_32: Option<NonZeroUsize>,
+    entry_64: Option<NonZeroUsize>,
+) -> &'static SmbiosResponse {
+    leak(SmbiosResponse {
+        revision: 0,
+        entry_32,
+        entry_64,
+    })
+}
+
+/// Build an [`EfiSystemTableResponse`] pointing at the given address.
+pub fn efi_system_table(address: usize) -> &'static EfiSystemTableResponse {
+    leak(EfiSystemTableResponse {
+        revision: 0,
+        address,
+    })
+}
+
+/// Build an [`EfiMemoryMapResponse`] holding a copy of the given raw memory
+/// map.
+pub fn efi_memory_map(
+    memmap: &[u8],
+    desc_size: u64,
+    desc_version: u32,
+) -> &'static EfiMemoryMapResponse {
+    let memmap: &[u8] = leak_slice(memmap.to_vec());
+    leak(EfiMemoryMapResponse {
+        revision: 0,
+        memmap: memmap.as_ptr().cast::<c_void>(),
+        memmap_size: memmap.len() as u64,
+        desc_size,
+        desc_version,
+    })
+}
+
+/// Build a [`DateAtBootResponse`] with the given UNIX timestamp.
+pub fn date_at_boot(timestamp: i64) -> &'static DateAtBootResponse {
+    leak(DateAtBootResponse {
+        revision: 0,
+        timestamp,
+    })
+}
+
+/// Build an [`ExecutableAddressResponse`].
+pub fn executable_address(
+    physical_base: u64,
+    virtual_base: u64,
+) -> &'static ExecutableAddressResponse {
+    leak(ExecutableAddressResponse {
+        revision: 0,
+        physical_base,
+        virtual_base,
+    })
+}
+
+/// Build an [`ExecutableCmdlineResponse`].
+pub fn executable_cmdline(cmdline: &str) -> &'static ExecutableCmdlineResponse {
+    leak(ExecutableCmdlineResponse {
+        revision: 0,
+        cmdline: leak_str(cmdline),
+    })
+}
+
+/// Build a [`DeviceTreeBlobResponse`] holding a copy of the given blob.
+pub fn device_tree_blob(dtb: &[u8]) -> &'static DeviceTreeBlobResponse {
+    let dtb: &[u8] = leak_slice(dtb.to_vec());
+    leak(DeviceTreeBlobResponse {
+        revision: 0,
+        dtb_ptr: dtb.as_ptr().cast::<c_void>(),
+    })
+}
+
+/// Build a [`BspHartidResponse`].
+#[cfg(target_arch = "riscv64")]
+pub fn bsp_hartid(bsp_hartid: u64) -> &'static BspHartidResponse {
+    leak(BspHartidResponse {
+        revision: 0,
+        bsp_hartid,
+    })
+}
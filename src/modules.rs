@@ -111,3 +111,5 @@ impl InternalModule {
         self.flags
     }
 }
+
+crate::layout::check_layout!(modules);
@@ -4,10 +4,15 @@ use core::sync::atomic::{AtomicPtr, Ordering};
 
 use bitflags::bitflags;
 
+#[cfg(all(feature = "alloc", not(target_arch = "loongarch64")))]
+mod launch;
+#[cfg(all(feature = "alloc", not(target_arch = "loongarch64")))]
+pub use launch::{start_all, CpuState, Launcher, Started, Timeout};
+
 /// A function pointer that the core will jump to when it is written to.
 #[repr(transparent)]
 pub struct GotoAddress {
-    inner: AtomicPtr<()>,
+    pub(crate) inner: AtomicPtr<()>,
 }
 impl GotoAddress {
     /// Set the goto address pointer. This will cause the core to jump to the
@@ -29,7 +34,7 @@ pub struct Cpu {
     /// The APIC ID, according to the ACPI MADT.
     pub lapic_id: u32,
 
-    _reserved: core::mem::MaybeUninit<u64>,
+    pub(crate) _reserved: core::mem::MaybeUninit<u64>,
 
     /// The address to jump to. Writing to this field will cause the core to
     /// jump to the given function. The function will receive a pointer to this
@@ -47,12 +52,12 @@ pub struct Cpu {
     /// The ACPI processor ID, according to the ACPI MADT.
     pub id: u32,
 
-    _reserved1: core::mem::MaybeUninit<u32>,
+    pub(crate) _reserved1: core::mem::MaybeUninit<u32>,
 
     /// The MPIDR of the CPU, according to the ACPI MADT or the device tree.
     pub mpidr: u64,
 
-    _reserved: core::mem::MaybeUninit<u64>,
+    pub(crate) _reserved: core::mem::MaybeUninit<u64>,
 
     /// The address to jump to. Writing to this field will cause the core to
     /// jump to the given function. The function will receive a pointer to this
@@ -73,7 +78,7 @@ pub struct Cpu {
     /// The hart ID, according to the ACPI MADT or the device tree.
     pub hartid: u64,
 
-    _reserved: core::mem::MaybeUninit<u64>,
+    pub(crate) _reserved: core::mem::MaybeUninit<u64>,
 
     /// The address to jump to. Writing to this field will cause the core to
     /// jump to the given function. The function will receive a pointer to this
@@ -88,7 +93,7 @@ pub struct Cpu {
 #[repr(C)]
 #[cfg(target_arch = "loongarch64")]
 pub struct Cpu {
-    _reserved: core::mem::MaybeUninit<u64>,
+    pub(crate) _reserved: core::mem::MaybeUninit<u64>,
 }
 
 bitflags! {
@@ -118,3 +123,5 @@ bitflags! {
     #[derive(Default, Clone, Copy)]
     pub struct ResponseFlags: u64 {}
 }
+
+crate::layout::check_layout!(mp);
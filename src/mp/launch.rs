@@ -0,0 +1,431 @@
+//! Safe application processor start-up.
+
+use alloc::{
+    alloc::{alloc, handle_alloc_error, Layout},
+    boxed::Box,
+    vec::Vec,
+};
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+use super::Cpu;
+use crate::{
+    percpu::{arch_id, CpuIndex},
+    response::MpResponse,
+};
+
+/// The alignment of the stacks allocated by [`Launcher::with_stack_size`].
+const STACK_ALIGN: usize = 16;
+
+/// State shared by every AP started by one [`Launcher::start`] call.
+struct Shared<F> {
+    f: F,
+    arrived: AtomicUsize,
+    states: &'static [AtomicU8],
+}
+
+/// The context of one AP, published through [`Cpu::extra`].
+struct Context<T, F: 'static> {
+    shared: &'static Shared<F>,
+    index: usize,
+    arg: UnsafeCell<Option<T>>,
+    stack_top: usize,
+}
+
+/// The start-up state of a CPU, as tracked by [`Started`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CpuState {
+    /// The CPU has not jumped to its goto address yet.
+    NotStarted,
+    /// The CPU has jumped to its goto address, but has not reached the
+    /// closure yet, for example because it is still switching stacks.
+    Jumped,
+    /// The CPU is running the closure. The boot processor is always in this
+    /// state.
+    Running,
+    /// The CPU was reported as failed with [`Started::fail`].
+    Failed,
+}
+impl CpuState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::NotStarted,
+            1 => Self::Jumped,
+            2 => Self::Running,
+            _ => Self::Failed,
+        }
+    }
+}
+impl fmt::Display for CpuState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotStarted => "not started",
+            Self::Jumped => "jumped",
+            Self::Running => "running",
+            Self::Failed => "failed",
+        })
+    }
+}
+
+/// Returns whether `cpu` is the boot processor.
+fn is_bsp(response: &MpResponse, cpu: &Cpu) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    return cpu.lapic_id == response.bsp_lapic_id();
+    #[cfg(target_arch = "aarch64")]
+    return cpu.mpidr == response.bsp_mpidr();
+    #[cfg(target_arch = "riscv64")]
+    return cpu.hartid == response.bsp_hartid();
+}
+
+/// Starts the application processors (APs) with a Rust closure and a per-CPU
+/// argument.
+///
+/// The closure runs on every CPU in [`MpResponse::cpus`] except the boot
+/// processor. The context of each AP, holding its argument, is allocated on
+/// the heap and published through [`Cpu::extra`], which is why
+/// [`start`](Self::start) is `unsafe`. Neither the closure nor the contexts
+/// are ever freed, since APs never return to the caller.
+///
+/// This is only available with the `alloc` feature.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "mock")] {
+/// use core::sync::atomic::{AtomicU32, Ordering};
+/// use limine::{mock, mp::Launcher};
+///
+/// static SUM: AtomicU32 = AtomicU32::new(0);
+///
+/// let response = mock::Mp::new(0).with_cpu(0, 0).with_cpu(1, 1).with_cpu(2, 2).build();
+/// // SAFETY: the APs are started once, and nothing else uses `extra`
+/// let started = unsafe {
+///     Launcher::new(response)
+///         .with_stack_size(0x10000)
+///         .start(|cpu| cpu.id * 10, |_cpu, arg| {
+///             SUM.fetch_add(arg, Ordering::SeqCst);
+///         })
+/// };
+/// assert_eq!(started.expected(), 2);
+///
+/// // Mock APs run on host threads once their goto address is written
+/// for &cpu in &response.cpus()[1..] {
+///     mock::start_ap(cpu);
+/// }
+/// started.wait();
+/// # while SUM.load(Ordering::SeqCst) != 30 { std::thread::yield_now() }
+/// assert_eq!(SUM.load(Ordering::SeqCst), 30);
+/// # }
+/// ```
+pub struct Launcher {
+    response: &'static MpResponse,
+    stack_size: Option<usize>,
+}
+impl Launcher {
+    /// Create a launcher for the CPUs in `response`.
+    pub fn new(response: &'static MpResponse) -> Self {
+        Self {
+            response,
+            stack_size: None,
+        }
+    }
+
+    /// Allocate a stack of `size` bytes for each AP, instead of using the
+    /// stack provided by the bootloader. The size is rounded up to a multiple
+    /// of 16 bytes. A size of 0 keeps the bootloader-provided stacks.
+    pub fn with_stack_size(mut self, size: usize) -> Self {
+        self.stack_size = (size != 0).then_some(size);
+        self
+    }
+
+    /// Start every AP. `arg` is called on the boot processor for each AP,
+    /// in order, to build the argument passed to `f` on that AP.
+    ///
+    /// If `f` returns, the AP spins forever.
+    ///
+    /// # Safety
+    /// The APs in the response must not have been started before, by this
+    /// function or by writing their goto address directly, and the
+    /// [`extra`](Cpu::extra) field of each AP must not be changed until that
+    /// AP is [running](CpuState::Running). Otherwise an AP may read another
+    /// launch's context as its own.
+    pub unsafe fn start<T, F>(self, mut arg: impl FnMut(&Cpu) -> T, f: F) -> Started
+    where
+        T: Send + 'static,
+        F: Fn(&'static Cpu, T) + Sync + 'static,
+    {
+        let states: Vec<AtomicU8> = self
+            .response
+            .cpus()
+            .iter()
+            .map(|&cpu| {
+                let state = if is_bsp(self.response, cpu) {
+                    CpuState::Running
+                } else {
+                    CpuState::NotStarted
+                };
+                AtomicU8::new(state as u8)
+            })
+            .collect();
+        let shared: &'static Shared<F> = Box::leak(Box::new(Shared {
+            f,
+            arrived: AtomicUsize::new(0),
+            states: states.leak(),
+        }));
+        let mut expected = 0;
+        for (index, &cpu) in self.response.cpus().iter().enumerate() {
+            if is_bsp(self.response, cpu) {
+                continue;
+            }
+            let stack_top = match self.stack_size {
+                Some(size) => {
+                    // Round the size up so that the top of the stack is
+                    // aligned too.
+                    let layout = Layout::from_size_align(size, STACK_ALIGN)
+                        .expect("stack size is too large")
+                        .pad_to_align();
+                    // SAFETY: `with_stack_size` never stores a size of 0.
+                    let stack = unsafe { alloc(layout) };
+                    if stack.is_null() {
+                        handle_alloc_error(layout);
+                    }
+                    stack as usize + layout.size()
+                }
+                None => 0,
+            };
+            let context: &'static Context<T, F> = Box::leak(Box::new(Context {
+                shared,
+                index,
+                arg: UnsafeCell::new(Some(arg(cpu))),
+                stack_top,
+            }));
+            cpu.extra
+                .store(context as *const _ as u64, Ordering::Release);
+            cpu.goto_address.write(trampoline::<T, F>);
+            expected += 1;
+        }
+        Started {
+            response: self.response,
+            arrived: &shared.arrived,
+            states: shared.states,
+            expected,
+        }
+    }
+}
+
+/// Start every AP on the bootloader-provided stacks. See [`Launcher`].
+///
+/// # Safety
+/// See [`Launcher::start`].
+pub unsafe fn start_all<T, F>(
+    response: &'static MpResponse,
+    arg: impl FnMut(&Cpu) -> T,
+    f: F,
+) -> Started
+where
+    T: Send + 'static,
+    F: Fn(&'static Cpu, T) + Sync + 'static,
+{
+    Launcher::new(response).start(arg, f)
+}
+
+/// The entry point of every AP, which switches to the allocated stack if
+/// there is one.
+unsafe extern "C" fn trampoline<T, F>(cpu: &Cpu) -> !
+where
+    T: Send + 'static,
+    F: Fn(&'static Cpu, T) + Sync + 'static,
+{
+    let context = &*(cpu.extra.load(Ordering::Acquire) as *const Context<T, F>);
+    context.shared.states[context.index].store(CpuState::Jumped as u8, Ordering::Release);
+    if context.stack_top == 0 {
+        ap_main::<T, F>(cpu);
+    }
+    #[cfg(target_arch = "x86_64")]
+    core::arch::asm!(
+        "mov rsp, {stack}",
+        "xor ebp, ebp",
+        "call {main}",
+        "ud2",
+        stack = in(reg) context.stack_top,
+        main = sym ap_main::<T, F>,
+        in("rdi") cpu,
+        options(noreturn),
+    );
+    #[cfg(target_arch = "aarch64")]
+    core::arch::asm!(
+        "mov sp, {stack}",
+        "mov x29, xzr",
+        "mov x30, xzr",
+        "bl {main}",
+        "udf #0",
+        stack = in(reg) context.stack_top,
+        main = sym ap_main::<T, F>,
+        in("x0") cpu,
+        options(noreturn),
+    );
+    #[cfg(target_arch = "riscv64")]
+    core::arch::asm!(
+        "mv sp, {stack}",
+        "li s0, 0",
+        "call {main}",
+        "unimp",
+        stack = in(reg) context.stack_top,
+        main = sym ap_main::<T, F>,
+        in("a0") cpu,
+        options(noreturn),
+    );
+}
+
+/// Check in, then run the closure with this AP's argument.
+unsafe extern "C" fn ap_main<T, F>(cpu: &Cpu) -> !
+where
+    T: Send + 'static,
+    F: Fn(&'static Cpu, T) + Sync + 'static,
+{
+    // SAFETY: the MP response, and so the CPU, is `'static`
+    let cpu: &'static Cpu = &*(cpu as *const Cpu);
+    let context = &*(cpu.extra.load(Ordering::Acquire) as *const Context<T, F>);
+    // SAFETY: only this AP reads its context's argument, exactly once
+    let arg = (*context.arg.get()).take().unwrap();
+    context.shared.states[context.index].store(CpuState::Running as u8, Ordering::Release);
+    context.shared.arrived.fetch_add(1, Ordering::Release);
+    (context.shared.f)(cpu, arg);
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+/// A handle to the APs started by a [`Launcher`], which tracks the
+/// [state](CpuState) of every CPU and can wait until every AP has checked in.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "mock")] {
+/// use limine::{mock, mp::{CpuState, Launcher}};
+///
+/// let response = mock::Mp::new(0).with_cpu(0, 0).with_cpu(1, 1).with_cpu(2, 2).build();
+/// let started = unsafe { Launcher::new(response).start(|_| (), |_, _| {}) };
+///
+/// // Only the first AP comes up
+/// mock::start_ap(response.cpus()[1]);
+/// # while started.arrived() != 1 { std::thread::yield_now() }
+/// let mut spins = 0;
+/// let timeout = started.wait_timeout(|| { spins += 1; spins > 1000 }).unwrap_err();
+/// assert_eq!(timeout.to_string(), "1 of 2 APs did not arrive");
+///
+/// let (cpu, state) = timeout.missing().next().unwrap();
+/// assert_eq!((cpu.id, state), (2, CpuState::NotStarted));
+///
+/// // A fault handler can record which AP failed
+/// started.fail(cpu);
+/// assert_eq!(started.state(cpu), Some(CpuState::Failed));
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Started {
+    response: &'static MpResponse,
+    arrived: &'static AtomicUsize,
+    states: &'static [AtomicU8],
+    expected: usize,
+}
+impl Started {
+    /// The number of APs that were started.
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// The number of APs that have checked in, just before running the
+    /// closure.
+    pub fn arrived(&self) -> usize {
+        self.arrived.load(Ordering::Acquire)
+    }
+
+    /// Returns whether every AP has checked in.
+    pub fn is_complete(&self) -> bool {
+        self.arrived() == self.expected
+    }
+
+    /// Spin until every AP has checked in.
+    pub fn wait(&self) {
+        while !self.is_complete() {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Spin until every AP has checked in, or until `timed_out` returns
+    /// `true`. `timed_out` is called once per iteration, so it can count
+    /// iterations or read a timer.
+    pub fn wait_timeout(&self, mut timed_out: impl FnMut() -> bool) -> Result<(), Timeout> {
+        while !self.is_complete() {
+            if timed_out() {
+                return Err(Timeout { started: *self });
+            }
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    /// Returns the state of `cpu`, or `None` if it is not in the MP response.
+    pub fn state(&self, cpu: &Cpu) -> Option<CpuState> {
+        let index = CpuIndex::new(self.response).index_of(arch_id(cpu))?;
+        Some(CpuState::from_u8(
+            self.states[index].load(Ordering::Acquire),
+        ))
+    }
+
+    /// Returns an iterator over every CPU, including the boot processor, and
+    /// its state.
+    pub fn states(&self) -> impl Iterator<Item = (&'static Cpu, CpuState)> {
+        let states = self.states;
+        self.response
+            .cpus()
+            .iter()
+            .zip(states)
+            .map(|(&cpu, state)| (cpu, CpuState::from_u8(state.load(Ordering::Acquire))))
+    }
+
+    /// Record that `cpu` has failed, for example from a fault handler during
+    /// start-up. Does nothing if `cpu` is not in the MP response.
+    pub fn fail(&self, cpu: &Cpu) {
+        if let Some(index) = CpuIndex::new(self.response).index_of(arch_id(cpu)) {
+            self.states[index].store(CpuState::Failed as u8, Ordering::Release);
+        }
+    }
+}
+
+/// The error returned by [`Started::wait_timeout`] if some APs did not check
+/// in.
+#[derive(Clone, Copy)]
+pub struct Timeout {
+    started: Started,
+}
+impl Timeout {
+    /// Returns an iterator over the APs that had not checked in when the wait
+    /// timed out, and their current state.
+    pub fn missing(&self) -> impl Iterator<Item = (&'static Cpu, CpuState)> {
+        self.started
+            .states()
+            .filter(|&(_, state)| state != CpuState::Running)
+    }
+}
+impl fmt::Debug for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.missing().map(|(cpu, state)| (cpu.id, state)))
+            .finish()
+    }
+}
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = self.missing().count();
+        write!(
+            f,
+            "{missing} of {} APs did not arrive",
+            self.started.expected
+        )
+    }
+}
@@ -0,0 +1,226 @@
+//! Per-CPU data, indexed densely by the CPUs in the [MP
+//! response](crate::response::MpResponse).
+//!
+//! Architecture IDs (the local APIC ID on x86_64, the MPIDR on aarch64 and
+//! the hart ID on riscv64) are sparse, so a [`CpuIndex`] maps them to
+//! indices `0..N` in the order of [`MpResponse::cpus`], which
+//! [`PerCpu`] uses to store one value per CPU.
+//!
+//! # Examples
+//! ```rust
+//! # #[cfg(feature = "mock")] {
+//! use core::sync::atomic::{AtomicU64, Ordering};
+//! use limine::{mock, percpu::{CpuIndex, PerCpu}};
+//!
+//! let response = mock::Mp::new(0).with_cpu(0, 0).with_cpu(1, 4).with_cpu(2, 8).build();
+//! let index = CpuIndex::new(response);
+//! assert_eq!(index.index_of(8), Some(2));
+//!
+//! let ticks: PerCpu<AtomicU64, 16> = PerCpu::new(&index, |_, _| AtomicU64::new(0)).unwrap();
+//!
+//! // On riscv64, the hart ID is usually kept in `tp` or `sscratch` by the kernel
+//! let hart_id = 4;
+//! ticks.current_with(&index, || hart_id).unwrap().fetch_add(1, Ordering::Relaxed);
+//! assert_eq!(ticks[1].load(Ordering::Relaxed), 1);
+//! # }
+//! ```
+
+use core::{fmt, mem::MaybeUninit, ops::Index};
+
+use crate::{mp::Cpu, response::MpResponse};
+
+/// The architecture-specific ID of a CPU: the local APIC ID on x86_64, the
+/// MPIDR on aarch64 and the hart ID on riscv64.
+#[cfg(target_arch = "x86_64")]
+pub type ArchId = u32;
+/// The architecture-specific ID of a CPU: the local APIC ID on x86_64, the
+/// MPIDR on aarch64 and the hart ID on riscv64.
+#[cfg(not(target_arch = "x86_64"))]
+pub type ArchId = u64;
+
+/// Returns the architecture-specific ID of a CPU from the MP response.
+#[cfg(not(target_arch = "loongarch64"))]
+pub fn arch_id(cpu: &Cpu) -> ArchId {
+    #[cfg(target_arch = "x86_64")]
+    return cpu.lapic_id;
+    #[cfg(target_arch = "aarch64")]
+    return cpu.mpidr;
+    #[cfg(target_arch = "riscv64")]
+    return cpu.hartid;
+}
+
+/// Read the architecture-specific ID of the current CPU: the x2APIC ID from
+/// CPUID leaf 0xB if available, or the initial APIC ID from leaf 1.
+#[cfg(target_arch = "x86_64")]
+pub fn current_arch_id() -> ArchId {
+    use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+    // SAFETY: CPUID is always available on x86_64
+    unsafe {
+        if __cpuid(0).eax >= 0xb {
+            let topology = __cpuid_count(0xb, 0);
+            if topology.ebx != 0 {
+                return topology.edx;
+            }
+        }
+        __cpuid(1).ebx >> 24
+    }
+}
+
+/// Read the architecture-specific ID of the current CPU: the affinity fields
+/// of `MPIDR_EL1`. This must run at EL1 or above.
+#[cfg(target_arch = "aarch64")]
+pub fn current_arch_id() -> ArchId {
+    const AFFINITY: u64 = 0xff_00ff_ffff;
+    let mpidr: u64;
+    // SAFETY: reading MPIDR_EL1 has no side effects
+    unsafe { core::arch::asm!("mrs {}, mpidr_el1", out(reg) mpidr, options(nomem, nostack)) };
+    mpidr & AFFINITY
+}
+
+/// A dense index of the CPUs in the MP response.
+#[derive(Clone, Copy)]
+pub struct CpuIndex<'a> {
+    cpus: &'a [&'a Cpu],
+}
+impl<'a> CpuIndex<'a> {
+    /// Index the CPUs of `response`, in the order of [`MpResponse::cpus`].
+    pub fn new(response: &'a MpResponse) -> Self {
+        Self {
+            cpus: response.cpus(),
+        }
+    }
+
+    /// The number of CPUs, including the boot processor.
+    pub fn len(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// Returns whether there are no CPUs.
+    pub fn is_empty(&self) -> bool {
+        self.cpus.is_empty()
+    }
+
+    /// Returns the CPU at `index`.
+    pub fn cpu(&self, index: usize) -> Option<&'a Cpu> {
+        self.cpus.get(index).copied()
+    }
+
+    /// Returns the index of the CPU with the given architecture-specific ID.
+    #[cfg(not(target_arch = "loongarch64"))]
+    pub fn index_of(&self, id: ArchId) -> Option<usize> {
+        // IDs are often already dense, so try the matching slot first.
+        let guess = usize::try_from(id).ok().and_then(|i| self.cpus.get(i));
+        if guess.is_some_and(|cpu| arch_id(cpu) == id) {
+            return usize::try_from(id).ok();
+        }
+        self.cpus.iter().position(|cpu| arch_id(cpu) == id)
+    }
+
+    /// Returns the index of the current CPU, using `read_id` to read its
+    /// architecture-specific ID. This is needed on riscv64, where the hart ID
+    /// cannot be read from supervisor mode and must be kept by the kernel.
+    #[cfg(not(target_arch = "loongarch64"))]
+    pub fn current_with(&self, read_id: impl FnOnce() -> ArchId) -> Option<usize> {
+        self.index_of(read_id())
+    }
+
+    /// Returns the index of the current CPU. See [`current_arch_id`].
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    pub fn current(&self) -> Option<usize> {
+        self.current_with(current_arch_id)
+    }
+}
+
+/// One value of type `T` per CPU, stored inline for up to `N` CPUs.
+pub struct PerCpu<T, const N: usize> {
+    values: [MaybeUninit<T>; N],
+    len: usize,
+}
+impl<T, const N: usize> PerCpu<T, N> {
+    /// Create one value for each CPU in `index` by calling `init` with its
+    /// index and CPU entry. Returns `None` if there are more than `N` CPUs.
+    pub fn new(index: &CpuIndex<'_>, mut init: impl FnMut(usize, &Cpu) -> T) -> Option<Self> {
+        if index.len() > N {
+            return None;
+        }
+        // SAFETY: an array of `MaybeUninit` does not need initialization
+        let mut this = Self {
+            values: unsafe { MaybeUninit::uninit().assume_init() },
+            len: 0,
+        };
+        for (i, cpu) in index.cpus.iter().enumerate() {
+            this.values[i].write(init(i, cpu));
+            this.len += 1;
+        }
+        Some(this)
+    }
+
+    /// The number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the values as a slice, in CPU index order.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` values are initialized
+        unsafe { core::slice::from_raw_parts(self.values.as_ptr().cast(), self.len) }
+    }
+
+    /// Returns the values as a mutable slice, in CPU index order.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` values are initialized
+        unsafe { core::slice::from_raw_parts_mut(self.values.as_mut_ptr().cast(), self.len) }
+    }
+
+    /// Returns the value of the CPU at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    /// Returns the value of the current CPU, using `read_id` to read its
+    /// architecture-specific ID. See [`CpuIndex::current_with`].
+    #[cfg(not(target_arch = "loongarch64"))]
+    pub fn current_with(
+        &self,
+        index: &CpuIndex<'_>,
+        read_id: impl FnOnce() -> ArchId,
+    ) -> Option<&T> {
+        self.get(index.current_with(read_id)?)
+    }
+
+    /// Returns the value of the current CPU. See [`CpuIndex::current`].
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    pub fn current(&self, index: &CpuIndex<'_>) -> Option<&T> {
+        self.get(index.current()?)
+    }
+
+    /// Returns an iterator over the values, in CPU index order.
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+}
+impl<T, const N: usize> Index<usize> for PerCpu<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.as_slice()[index]
+    }
+}
+impl<T, const N: usize> Drop for PerCpu<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: the first `len` values are initialized, and are not used
+        // again
+        unsafe { core::ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+impl<T: fmt::Debug, const N: usize> fmt::Debug for PerCpu<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,72 @@
+//! Access to firmware tables in physical memory, through the higher-half
+//! direct map.
+
+use crate::{
+    addr::{PhysAddr, VirtAddr},
+    response::HhdmResponse,
+    Error,
+};
+
+/// Convert the address of a firmware table given by a response to a physical
+/// address. Base revision 3 gives physical addresses, while earlier base
+/// revisions give addresses in the higher-half direct map.
+pub(crate) fn response_address(address: u64, hhdm: &HhdmResponse) -> PhysAddr {
+    match hhdm.virt_to_phys(VirtAddr::new(address)) {
+        Some(phys) if hhdm.offset() != 0 => phys,
+        _ => PhysAddr::new(address),
+    }
+}
+
+/// Returns a pointer to `phys` through the direct map at `hhdm_offset`.
+pub(crate) fn translate<T>(phys: PhysAddr, hhdm_offset: u64) -> Result<*const T, Error> {
+    if phys.as_u64() == 0 {
+        return Err(Error::NullPointer);
+    }
+    let ptr = phys
+        .to_virt(hhdm_offset)
+        .and_then(VirtAddr::as_ptr::<T>)
+        .ok_or(Error::Unmapped)?;
+    Ok(ptr.cast_const())
+}
+
+/// Returns `len` bytes at `phys`, through the direct map at `hhdm_offset`.
+///
+/// # Safety
+/// The direct map must cover `len` bytes at `phys`, and they must not be
+/// modified during `'a`.
+pub(crate) unsafe fn map<'a>(
+    phys: PhysAddr,
+    hhdm_offset: u64,
+    len: usize,
+) -> Result<&'a [u8], Error> {
+    let ptr = translate::<u8>(phys, hhdm_offset)?;
+    if (ptr as usize).checked_add(len).is_none() {
+        return Err(Error::Unmapped);
+    }
+    Ok(core::slice::from_raw_parts(ptr, len))
+}
+
+pub(crate) fn read<const N: usize>(bytes: &[u8], offset: usize) -> Option<[u8; N]> {
+    bytes.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+pub(crate) fn read_u8(bytes: &[u8], offset: usize) -> Option<u8> {
+    bytes.get(offset).copied()
+}
+
+pub(crate) fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    read(bytes, offset).map(u16::from_le_bytes)
+}
+
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    read(bytes, offset).map(u32::from_le_bytes)
+}
+
+pub(crate) fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    read(bytes, offset).map(u64::from_le_bytes)
+}
+
+/// Returns whether the bytes of a table sum to zero.
+pub(crate) fn checksum(bytes: &[u8]) -> bool {
+    bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) == 0
+}
@@ -6,6 +6,10 @@ use crate::{modules::InternalModule, mp, paging, response::*};
 
 macro_rules! impl_base_fns {
     ($latest_revision:expr, $response:ty, $magic:expr, { $($(#[$attr:meta])* $field:ident: $val:expr),* $(,)? }) => {
+        /// The ID of this kind of request. This includes the magic number and
+        /// the request-specific ID.
+        pub const ID: [u64; 4] = $magic;
+
         /// Create a new request with the latest revision.
         pub const fn new() -> Self {
             Self::with_revision($latest_revision)
@@ -14,7 +18,7 @@ macro_rules! impl_base_fns {
         /// Create a new request with the given revision.
         pub const fn with_revision(revision: u64) -> Self {
             Self {
-                id: $magic,
+                id: Self::ID,
                 revision,
                 response: Response::none(),
                 $($(#[$attr])* $field: $val),*
@@ -44,6 +48,19 @@ macro_rules! impl_base_fns {
         pub fn get_response_mut(&mut self) -> Option<&mut $response> {
             self.response.get_mut()
         }
+
+        /// Answer this request with the given response, as the bootloader
+        /// would. Passing `None` removes any previously set response. Only
+        /// available with the `mock` feature; see [`mock`](crate::mock) for
+        /// ways to build responses.
+        ///
+        /// # Safety
+        /// No other thread may access this request while the response is being
+        /// set.
+        #[cfg(all(feature = "mock", not(target_os = "none")))]
+        pub unsafe fn set_response(&self, response: Option<&'static $response>) {
+            self.response.set(response)
+        }
     };
 }
 
@@ -81,16 +98,17 @@ pub struct RequestsStartMarker {
     id: [u64; 4],
 }
 impl RequestsStartMarker {
+    /// The magic number identifying the start marker.
+    pub const ID: [u64; 4] = [
+        0xf6b8f4b39de7d1ae,
+        0xfab91a6940fcb9cf,
+        0x785c6ed015d3e316,
+        0x181e920a7852b9d9,
+    ];
+
     /// Create a new request start marker
     pub const fn new() -> Self {
-        Self {
-            id: [
-                0xf6b8f4b39de7d1ae,
-                0xfab91a6940fcb9cf,
-                0x785c6ed015d3e316,
-                0x181e920a7852b9d9,
-            ],
-        }
+        Self { id: Self::ID }
     }
 }
 
@@ -100,14 +118,131 @@ pub struct RequestsEndMarker {
     id: [u64; 2],
 }
 impl RequestsEndMarker {
+    /// The magic number identifying the end marker.
+    pub const ID: [u64; 2] = [0xadc0e0531bb10d03, 0x9572709f31764c62];
+
     /// Create a new request end marker
     pub const fn new() -> Self {
-        Self {
-            id: [0xadc0e0531bb10d03, 0x9572709f31764c62],
-        }
+        Self { id: Self::ID }
     }
 }
 
+/// Declare a [`BaseRevision`](crate::BaseRevision) tag, a set of requests and
+/// the markers around them in one place.
+///
+/// The macro places the start marker in the `.requests_start_marker` section,
+/// the end marker in the `.requests_end_marker` section, and the base revision
+/// and every listed request in the `.requests` section, all marked `#[used]`.
+/// Your linker script is expected to keep these sections and place them in
+/// that order. It also declares a static of a newly generated type, with one
+/// accessor per request, so that code can ask for `REQUESTS.memory_map()`
+/// instead of naming individual statics.
+///
+/// Each request is written as `name: Type`, which uses `Type::new()`, or
+/// `name: Type = expr` to construct it differently. The base revision defaults
+/// to [`BaseRevision::new`](crate::BaseRevision::new), and can be overridden
+/// with a leading `base_revision = expr;`.
+///
+/// # Usage
+/// ```rust
+/// use limine::{request::*, BaseRevision};
+///
+/// limine::requests! {
+///     /// All requests made by this executable.
+///     pub static REQUESTS: Requests {
+///         base_revision = BaseRevision::with_revision(3);
+///         memory_map: MemoryMapRequest,
+///         hhdm: HhdmRequest,
+///         stack_size: StackSizeRequest = StackSizeRequest::new().with_size(0x100000),
+///     }
+/// }
+///
+/// # fn dummy() {
+/// // ...later, in our code
+/// assert!(REQUESTS.base_revision().is_supported());
+/// let memory_map = REQUESTS.memory_map().get_response().unwrap();
+/// # }
+/// assert_eq!(REQUESTS.stack_size().size(), 0x100000);
+/// ```
+#[macro_export]
+macro_rules! requests {
+    (
+        @impl [$(#[$attr:meta])*] $vis:vis $name:ident $ty:ident [$base_revision:expr]
+        $($(#[$field_attr:meta])* $field:ident: $field_ty:ty $(= $init:expr)?),* $(,)?
+    ) => {
+        #[doc = concat!("Accessors for the requests in [`", stringify!($name), "`].")]
+        $vis struct $ty;
+
+        $(#[$attr])*
+        $vis static $name: $ty = $ty;
+
+        impl $ty {
+            /// Returns the base revision tag.
+            #[allow(dead_code)]
+            $vis fn base_revision(&self) -> &'static $crate::BaseRevision {
+                #[used]
+                #[link_section = ".requests"]
+                static BASE_REVISION: $crate::BaseRevision = $base_revision;
+                &BASE_REVISION
+            }
+
+            $(
+                $(#[$field_attr])*
+                #[doc = concat!("Returns the `", stringify!($field), "` request.")]
+                #[allow(dead_code)]
+                $vis fn $field(&self) -> &'static $field_ty {
+                    #[used]
+                    #[link_section = ".requests"]
+                    static REQUEST: $field_ty = $crate::__requests_init!($field_ty $(, $init)?);
+                    &REQUEST
+                }
+            )*
+        }
+
+        const _: () = {
+            #[used]
+            #[link_section = ".requests_start_marker"]
+            static START_MARKER: $crate::request::RequestsStartMarker =
+                $crate::request::RequestsStartMarker::new();
+
+            #[used]
+            #[link_section = ".requests_end_marker"]
+            static END_MARKER: $crate::request::RequestsEndMarker =
+                $crate::request::RequestsEndMarker::new();
+        };
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: $ty:ident {
+            base_revision = $base_revision:expr;
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::requests!(@impl [$(#[$attr])*] $vis $name $ty [$base_revision] $($fields)*);
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $name:ident: $ty:ident {
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::requests!(
+            @impl [$(#[$attr])*] $vis $name $ty [$crate::BaseRevision::new()] $($fields)*
+        );
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __requests_init {
+    ($ty:ty) => {
+        <$ty>::new()
+    };
+    ($ty:ty, $init:expr) => {
+        $init
+    };
+}
+
 #[repr(transparent)]
 struct Response<T> {
     inner: UnsafeCell<Option<NonNull<T>>>,
@@ -121,6 +256,10 @@ impl<T> Response<T> {
     pub fn get_mut(&mut self) -> Option<&mut T> {
         Some(unsafe { core::ptr::read_volatile(self.inner.get())?.as_mut() })
     }
+    #[cfg(all(feature = "mock", not(target_os = "none")))]
+    pub unsafe fn set(&self, response: Option<&'static T>) {
+        core::ptr::write_volatile(self.inner.get(), response.map(NonNull::from))
+    }
 }
 impl<T> Response<T> {
     pub const fn none() -> Self {
@@ -967,3 +1106,5 @@ impl BspHartidRequest {
         {}
     );
 }
+
+crate::layout::check_layout!(request);
@@ -7,11 +7,13 @@ use core::{
 };
 
 use crate::{
-    file,
+    addr::{PhysAddr, VirtAddr},
+    efi, error, fdt, file,
     firmware_type::FirmwareType,
     framebuffer::{Framebuffer, RawFramebuffer},
     memory_map, mp,
     paging::Mode,
+    Error,
 };
 
 macro_rules! impl_base_fns {
@@ -27,9 +29,9 @@ macro_rules! impl_base_fns {
 /// request](crate::request::BootloaderInfoRequest).
 #[repr(C)]
 pub struct BootloaderInfoResponse {
-    revision: u64,
-    name: *const c_char,
-    version: *const c_char,
+    pub(crate) revision: u64,
+    pub(crate) name: *const c_char,
+    pub(crate) version: *const c_char,
 }
 unsafe impl Sync for BootloaderInfoResponse {}
 unsafe impl Send for BootloaderInfoResponse {}
@@ -37,22 +39,61 @@ impl BootloaderInfoResponse {
     impl_base_fns!();
 
     /// Returns the name of the loading bootloader.
+    ///
+    /// # Panics
+    /// Panics if the name is not valid UTF-8. See [`try_name`](Self::try_name)
+    /// for a non-panicking version.
     pub fn name(&self) -> &str {
         unsafe { CStr::from_ptr(self.name) }.to_str().unwrap()
     }
 
     /// Returns the version of the loading bootloader.
+    ///
+    /// # Panics
+    /// Panics if the version is not valid UTF-8. See
+    /// [`try_version`](Self::try_version) for a non-panicking version.
     pub fn version(&self) -> &str {
         unsafe { CStr::from_ptr(self.version) }.to_str().unwrap()
     }
+
+    /// Returns the name of the loading bootloader, or an error if it is null,
+    /// unterminated or not valid UTF-8.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "mock")] {
+    /// use limine::{mock, request::BootloaderInfoRequest};
+    ///
+    /// static BOOTLOADER_INFO_REQUEST: BootloaderInfoRequest = BootloaderInfoRequest::new();
+    /// # unsafe {
+    /// #     BOOTLOADER_INFO_REQUEST.set_response(Some(mock::bootloader_info("Limine", "8.0.0")))
+    /// # };
+    ///
+    /// if let Some(info) = BOOTLOADER_INFO_REQUEST.get_response() {
+    ///     match info.try_name() {
+    ///         Ok(name) => assert_eq!(name, "Limine"),
+    ///         Err(err) => panic!("bad bootloader name: {err}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn try_name(&self) -> Result<&str, Error> {
+        unsafe { error::str(self.name) }
+    }
+
+    /// Returns the version of the loading bootloader, or an error if it is
+    /// null, unterminated or not valid UTF-8.
+    pub fn try_version(&self) -> Result<&str, Error> {
+        unsafe { error::str(self.version) }
+    }
 }
 
 /// A response to a [firmware type request
 /// ](crate::request::FirmwareTypeRequest).
 #[repr(C)]
 pub struct FirmwareTypeResponse {
-    revision: u64,
-    firmware_type: FirmwareType,
+    pub(crate) revision: u64,
+    pub(crate) firmware_type: FirmwareType,
 }
 impl FirmwareTypeResponse {
     impl_base_fns!();
@@ -68,7 +109,7 @@ impl FirmwareTypeResponse {
 /// request.
 #[repr(C)]
 pub struct StackSizeResponse {
-    revision: u64,
+    pub(crate) revision: u64,
 }
 impl StackSizeResponse {
     impl_base_fns!();
@@ -78,8 +119,8 @@ impl StackSizeResponse {
 /// request](crate::request::HhdmRequest).
 #[repr(C)]
 pub struct HhdmResponse {
-    revision: u64,
-    offset: u64,
+    pub(crate) revision: u64,
+    pub(crate) offset: u64,
 }
 impl HhdmResponse {
     impl_base_fns!();
@@ -110,14 +151,61 @@ impl HhdmResponse {
     pub fn offset(&self) -> u64 {
         self.offset
     }
+
+    /// Convert a physical address to its virtual address in the higher-half
+    /// direct map, returning `None` on overflow.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "mock")] {
+    /// use limine::addr::{PhysAddr, VirtAddr};
+    /// # let hhdm = limine::mock::hhdm(0xffff_8000_0000_0000);
+    ///
+    /// let virt = hhdm.phys_to_virt(PhysAddr::new(0x1000)).unwrap();
+    /// assert_eq!(virt, VirtAddr::new(0xffff_8000_0000_1000));
+    /// assert_eq!(hhdm.virt_to_phys(virt), Some(PhysAddr::new(0x1000)));
+    /// assert_eq!(hhdm.phys_to_virt(PhysAddr::new(u64::MAX)), None);
+    /// # }
+    /// ```
+    pub fn phys_to_virt(&self, phys: PhysAddr) -> Option<VirtAddr> {
+        phys.to_virt(self.offset)
+    }
+
+    /// Convert a virtual address in the higher-half direct map to its physical
+    /// address, returning `None` if it lies below the direct map. See the note
+    /// on [`offset`](Self::offset).
+    pub fn virt_to_phys(&self, virt: VirtAddr) -> Option<PhysAddr> {
+        virt.to_phys(self.offset)
+    }
+
+    /// Returns a pointer to `phys` through the higher-half direct map, or
+    /// `None` if the address overflows.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "mock")] {
+    /// # use limine::addr::PhysAddr;
+    /// # let entry = limine::memory_map::Entry {
+    /// #     base: 0x1000,
+    /// #     length: 0x1000,
+    /// #     entry_type: limine::memory_map::EntryType::USABLE,
+    /// # };
+    /// # let hhdm = limine::mock::hhdm(0xffff_8000_0000_0000);
+    /// let frame: *mut [u8; 4096] = hhdm.as_ptr(entry.base_addr()).unwrap();
+    /// # assert_eq!(frame as usize, 0xffff_8000_0000_1000);
+    /// # }
+    /// ```
+    pub fn as_ptr<T>(&self, phys: PhysAddr) -> Option<*mut T> {
+        self.phys_to_virt(phys)?.as_ptr()
+    }
 }
 
 /// A response to a [framebuffer request](crate::request::FramebufferRequest).
 #[repr(C)]
 pub struct FramebufferResponse {
-    revision: u64,
-    framebuffer_ct: u64,
-    framebuffers: *const *const RawFramebuffer,
+    pub(crate) revision: u64,
+    pub(crate) framebuffer_ct: u64,
+    pub(crate) framebuffers: *const *const RawFramebuffer,
 }
 unsafe impl Sync for FramebufferResponse {}
 unsafe impl Send for FramebufferResponse {}
@@ -136,8 +224,8 @@ impl FramebufferResponse {
 /// A response to a [paging mode request](crate::request::PagingModeRequest).
 #[repr(C)]
 pub struct PagingModeResponse {
-    revision: u64,
-    mode: Mode,
+    pub(crate) revision: u64,
+    pub(crate) mode: Mode,
 }
 impl PagingModeResponse {
     impl_base_fns!();
@@ -159,17 +247,17 @@ pub type SmpResponse = MpResponse;
 #[repr(C)]
 pub struct MpResponse {
     #[cfg(not(target_arch = "loongarch64"))]
-    revision: u64,
+    pub(crate) revision: u64,
     #[cfg(not(target_arch = "loongarch64"))]
-    flags: mp::ResponseFlags,
+    pub(crate) flags: mp::ResponseFlags,
     #[cfg(target_arch = "x86_64")]
-    bsp_lapic_id: u32,
+    pub(crate) bsp_lapic_id: u32,
     #[cfg(target_arch = "aarch64")]
-    bsp_mpidr: u64,
+    pub(crate) bsp_mpidr: u64,
     #[cfg(target_arch = "riscv64")]
-    bsp_hartid: u64,
-    cpu_ct: u64,
-    cpus: *mut *mut mp::Cpu,
+    pub(crate) bsp_hartid: u64,
+    pub(crate) cpu_ct: u64,
+    pub(crate) cpus: *mut *mut mp::Cpu,
 }
 unsafe impl Sync for MpResponse {}
 unsafe impl Send for MpResponse {}
@@ -214,9 +302,9 @@ impl MpResponse {
 /// A response to a [memory map request](crate::request::MemoryMapRequest).
 #[repr(C)]
 pub struct MemoryMapResponse {
-    revision: u64,
-    entry_ct: u64,
-    entries: *mut *mut memory_map::Entry,
+    pub(crate) revision: u64,
+    pub(crate) entry_ct: u64,
+    pub(crate) entries: *mut *mut memory_map::Entry,
 }
 unsafe impl Sync for MemoryMapResponse {}
 unsafe impl Send for MemoryMapResponse {}
@@ -236,12 +324,21 @@ impl MemoryMapResponse {
     pub fn entries_mut(&mut self) -> &mut [&mut memory_map::Entry] {
         unsafe { core::slice::from_raw_parts_mut(self.entries.cast(), self.entry_ct as usize) }
     }
+
+    /// Copies the memory map into `buf` and normalizes it. See
+    /// [`normalize`](memory_map::normalize) for more information.
+    pub fn normalize<'a>(
+        &self,
+        buf: &'a mut [memory_map::Entry],
+    ) -> Result<memory_map::Normalized<'a>, Error> {
+        memory_map::normalize(self.entries(), buf)
+    }
 }
 
 /// A response to a [executable file request](crate::request::ExecutableFileRequest).
 #[repr(C)]
 pub struct EntryPointResponse {
-    revision: u64,
+    pub(crate) revision: u64,
 }
 impl EntryPointResponse {
     impl_base_fns!();
@@ -254,8 +351,8 @@ pub type KernelFileResponse = ExecutableFileResponse;
 /// A response to a [executable file request](crate::request::ExecutableFileRequest).
 #[repr(C)]
 pub struct ExecutableFileResponse {
-    revision: u64,
-    file: *const file::File,
+    pub(crate) revision: u64,
+    pub(crate) file: *const file::File,
 }
 unsafe impl Sync for ExecutableFileResponse {}
 unsafe impl Send for ExecutableFileResponse {}
@@ -271,9 +368,9 @@ impl ExecutableFileResponse {
 /// A response to a [module request](crate::request::ModuleRequest).
 #[repr(C)]
 pub struct ModuleResponse {
-    revision: u64,
-    module_ct: u64,
-    modules: *const *const file::File,
+    pub(crate) revision: u64,
+    pub(crate) module_ct: u64,
+    pub(crate) modules: *const *const file::File,
 }
 unsafe impl Sync for ModuleResponse {}
 unsafe impl Send for ModuleResponse {}
@@ -290,8 +387,8 @@ impl ModuleResponse {
 /// A response to a [rsdp request](crate::request::RsdpRequest).
 #[repr(C)]
 pub struct RsdpResponse {
-    revision: u64,
-    address: usize,
+    pub(crate) revision: u64,
+    pub(crate) address: usize,
 }
 unsafe impl Sync for RsdpResponse {}
 unsafe impl Send for RsdpResponse {}
@@ -299,17 +396,27 @@ impl RsdpResponse {
     impl_base_fns!();
 
     /// Returns the address of the RSDP table in the ACPI.
+    ///
+    /// From base revision 3 this is a physical address, which can be turned
+    /// into a pointer with [`PhysAddr::from`] and [`HhdmResponse::as_ptr`].
+    /// Earlier base revisions return a virtual address in the higher-half
+    /// direct map instead.
     pub fn address(&self) -> usize {
         self.address
     }
 }
 
 /// A response to a [smbios request](crate::request::SmbiosRequest).
+///
+/// From base revision 3 the entry points are physical addresses, which can be
+/// turned into pointers with [`PhysAddr::from`] and [`HhdmResponse::as_ptr`].
+/// Earlier base revisions return virtual addresses in the higher-half direct
+/// map instead.
 #[repr(C)]
 pub struct SmbiosResponse {
-    revision: u64,
-    entry_32: Option<NonZeroUsize>,
-    entry_64: Option<NonZeroUsize>,
+    pub(crate) revision: u64,
+    pub(crate) entry_32: Option<NonZeroUsize>,
+    pub(crate) entry_64: Option<NonZeroUsize>,
 }
 unsafe impl Sync for SmbiosResponse {}
 unsafe impl Send for SmbiosResponse {}
@@ -329,15 +436,17 @@ impl SmbiosResponse {
 /// A response to a [system table request](crate::request::EfiSystemTableRequest).
 #[repr(C)]
 pub struct EfiSystemTableResponse {
-    revision: u64,
-    address: usize,
+    pub(crate) revision: u64,
+    pub(crate) address: usize,
 }
 unsafe impl Sync for EfiSystemTableResponse {}
 unsafe impl Send for EfiSystemTableResponse {}
 impl EfiSystemTableResponse {
     impl_base_fns!();
 
-    /// Returns the address of the EFI system table.
+    /// Returns the address of the EFI system table. See
+    /// [`SystemTable::from_response`](crate::efi::SystemTable::from_response)
+    /// for reading it.
     pub fn address(&self) -> usize {
         self.address
     }
@@ -346,11 +455,11 @@ impl EfiSystemTableResponse {
 /// A response to a [memory map request](crate::request::EfiMemoryMapRequest).
 #[repr(C)]
 pub struct EfiMemoryMapResponse {
-    revision: u64,
-    memmap: *const c_void,
-    memmap_size: u64,
-    desc_size: u64,
-    desc_version: u32,
+    pub(crate) revision: u64,
+    pub(crate) memmap: *const c_void,
+    pub(crate) memmap_size: u64,
+    pub(crate) desc_size: u64,
+    pub(crate) desc_version: u32,
 }
 unsafe impl Sync for EfiMemoryMapResponse {}
 unsafe impl Send for EfiMemoryMapResponse {}
@@ -374,6 +483,19 @@ impl EfiMemoryMapResponse {
     pub fn desc_version(&self) -> u32 {
         self.desc_version
     }
+
+    /// Returns an iterator over the typed descriptors of the EFI memory map.
+    /// Returns an error if the map is null or its descriptor size is smaller
+    /// than an [`efi::MemoryDescriptor`].
+    pub fn descriptors(&self) -> Result<efi::Descriptors<'_>, Error> {
+        if self.memmap.is_null() {
+            return Err(Error::NullPointer);
+        }
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self.memmap.cast::<u8>(), self.memmap_size as usize)
+        };
+        efi::Descriptors::new(bytes, self.desc_size as usize)
+    }
 }
 
 #[deprecated(since = "0.4.0", note = "please use `DateAtBootResponse` instead")]
@@ -383,8 +505,8 @@ pub type BootTimeResponse = DateAtBootResponse;
 /// A response to a [date at boot request](crate::request::DateAtBootRequest).
 #[repr(C)]
 pub struct DateAtBootResponse {
-    revision: u64,
-    timestamp: i64,
+    pub(crate) revision: u64,
+    pub(crate) timestamp: i64,
 }
 impl DateAtBootResponse {
     impl_base_fns!();
@@ -432,9 +554,9 @@ pub type KernelAddressResponse = ExecutableAddressResponse;
 /// ````
 #[repr(C)]
 pub struct ExecutableAddressResponse {
-    revision: u64,
-    physical_base: u64,
-    virtual_base: u64,
+    pub(crate) revision: u64,
+    pub(crate) physical_base: u64,
+    pub(crate) virtual_base: u64,
 }
 impl ExecutableAddressResponse {
     impl_base_fns!();
@@ -461,8 +583,8 @@ impl ExecutableAddressResponse {
 /// ````
 #[repr(C)]
 pub struct ExecutableCmdlineResponse {
-    revision: u64,
-    cmdline: *const c_char,
+    pub(crate) revision: u64,
+    pub(crate) cmdline: *const c_char,
 }
 unsafe impl Sync for ExecutableCmdlineResponse {}
 unsafe impl Send for ExecutableCmdlineResponse {}
@@ -473,13 +595,19 @@ impl ExecutableCmdlineResponse {
     pub fn cmdline(&self) -> &CStr {
         unsafe { CStr::from_ptr(self.cmdline) }
     }
+
+    /// Returns the command line as a string, or an error if it is null,
+    /// unterminated or not valid UTF-8.
+    pub fn try_cmdline_str(&self) -> Result<&str, Error> {
+        unsafe { error::str(self.cmdline) }
+    }
 }
 
 /// A response to a [device tree blob request](crate::request::DeviceTreeBlobRequest).
 #[repr(C)]
 pub struct DeviceTreeBlobResponse {
-    revision: u64,
-    dtb_ptr: *const c_void,
+    pub(crate) revision: u64,
+    pub(crate) dtb_ptr: *const c_void,
 }
 unsafe impl Sync for DeviceTreeBlobResponse {}
 unsafe impl Send for DeviceTreeBlobResponse {}
@@ -490,14 +618,19 @@ impl DeviceTreeBlobResponse {
     pub fn dtb_ptr(&self) -> *const () {
         self.dtb_ptr.cast()
     }
+
+    /// Returns a reader for the device tree blob, after validating its header.
+    pub fn fdt(&self) -> Result<fdt::Fdt<'_>, Error> {
+        unsafe { fdt::Fdt::from_ptr(self.dtb_ptr.cast()) }
+    }
 }
 
 /// A response to a [bsp hardid request](crate::request::BspHartidRequest).
 #[cfg(target_arch = "riscv64")]
 #[repr(C)]
 pub struct BspHartidResponse {
-    revision: u64,
-    bsp_hartid: u64,
+    pub(crate) revision: u64,
+    pub(crate) bsp_hartid: u64,
 }
 #[cfg(target_arch = "riscv64")]
 impl BspHartidResponse {
@@ -509,3 +642,5 @@ impl BspHartidResponse {
         self.bsp_hartid
     }
 }
+
+crate::layout::check_layout!(response);
@@ -0,0 +1,281 @@
+//! Host-side inspection of compiled executables.
+//!
+//! This module finds every Limine request, [`BaseRevision`] tag and request
+//! marker in a little-endian ELF64 image, so that a build can verify that no
+//! request was dropped by the linker or placed outside of the
+//! [`RequestsStartMarker`]/[`RequestsEndMarker`] range. It does not need `std`,
+//! but is only useful on the host; the `limine-scan` tool in this repository is
+//! a thin command line wrapper around it.
+//!
+//! This module is only available with the `scan` feature.
+//!
+//! # Examples
+//! ```rust,no_run
+//! let image = std::fs::read("kernel.elf").unwrap();
+//! let scan = limine::scan::Scan::new(&image).unwrap();
+//!
+//! for request in scan.requests() {
+//!     println!(
+//!         "{} (revision {}) in {}",
+//!         request.name.unwrap_or("unknown request"),
+//!         request.revision,
+//!         request.location.section,
+//!     );
+//! }
+//! ```
+
+use crate::{
+    elf::{Elf, Section, SHF_ALLOC, SHT_NOBITS},
+    request::{self, RequestsEndMarker, RequestsStartMarker},
+    BaseRevision, Error,
+};
+
+/// The part of the request ID shared by all requests.
+const COMMON_MAGIC: [u64; 2] = [request::HhdmRequest::ID[0], request::HhdmRequest::ID[1]];
+
+macro_rules! known_requests {
+    ($($name:ident),* $(,)?) => {
+        &[$((stringify!($name), request::$name::ID)),*]
+    };
+}
+
+/// Every request known to this crate, by name. Architecture-specific requests
+/// are listed explicitly, since the scanned image need not target the host.
+const KNOWN_REQUESTS: &[(&str, [u64; 4])] = {
+    const COMMON: &[(&str, [u64; 4])] = known_requests!(
+        BootloaderInfoRequest,
+        FirmwareTypeRequest,
+        StackSizeRequest,
+        HhdmRequest,
+        FramebufferRequest,
+        PagingModeRequest,
+        MpRequest,
+        MemoryMapRequest,
+        EntryPointRequest,
+        ExecutableFileRequest,
+        ModuleRequest,
+        RsdpRequest,
+        SmbiosRequest,
+        EfiSystemTableRequest,
+        EfiMemoryMapRequest,
+        DateAtBootRequest,
+        ExecutableAddressRequest,
+        ExecutableCmdlineRequest,
+        DeviceTreeBlobRequest,
+    );
+    const ALL: [(&str, [u64; 4]); COMMON.len() + 1] = {
+        let mut all = [(
+            "BspHartidRequest",
+            [
+                COMMON_MAGIC[0],
+                COMMON_MAGIC[1],
+                0x1369359f025525f9,
+                0x2ff2a56178391bb6,
+            ],
+        ); COMMON.len() + 1];
+        let mut i = 0;
+        while i < COMMON.len() {
+            all[i] = COMMON[i];
+            i += 1;
+        }
+        all
+    };
+    &ALL
+};
+
+/// Where an item was found in the executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location<'a> {
+    /// The name of the section containing the item.
+    pub section: &'a str,
+    /// The offset of the item from the start of its section.
+    pub section_offset: u64,
+    /// The offset of the item from the start of the file.
+    pub file_offset: u64,
+    /// The virtual address the item will be loaded at.
+    pub address: u64,
+}
+
+/// A [`BaseRevision`] tag found in the executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoundBaseRevision<'a> {
+    /// The revision requested by the tag.
+    pub revision: u64,
+    /// Where the tag was found.
+    pub location: Location<'a>,
+    /// Whether the tag lies between the start and end markers, or `None` if
+    /// the executable does not contain both markers.
+    pub in_markers: Option<bool>,
+}
+
+/// A request found in the executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoundRequest<'a> {
+    /// The name of the request type, such as `"MemoryMapRequest"`, or `None`
+    /// if the ID carries the common magic number but is not known to this
+    /// crate.
+    pub name: Option<&'static str>,
+    /// The full ID of the request.
+    pub id: [u64; 4],
+    /// The revision of the request.
+    pub revision: u64,
+    /// Where the request was found.
+    pub location: Location<'a>,
+    /// Whether the request lies between the start and end markers, or `None`
+    /// if the executable does not contain both markers.
+    pub in_markers: Option<bool>,
+}
+
+fn read_words<const N: usize>(data: &[u8], offset: usize) -> Option<[u64; N]> {
+    let mut words = [0; N];
+    for (i, word) in words.iter_mut().enumerate() {
+        let bytes = data.get(offset + i * 8..offset + i * 8 + 8)?;
+        *word = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+    Some(words)
+}
+
+/// The result of scanning an executable.
+#[derive(Clone, Copy)]
+pub struct Scan<'a> {
+    elf: Elf<'a>,
+    start_marker: Option<Location<'a>>,
+    end_marker: Option<Location<'a>>,
+}
+impl<'a> Scan<'a> {
+    /// Scan the given ELF image.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use limine::{request::MemoryMapRequest, scan::Scan};
+    ///
+    /// #[used]
+    /// #[link_section = ".requests"]
+    /// static MEMORY_MAP_REQUEST: MemoryMapRequest = MemoryMapRequest::with_revision(2);
+    ///
+    /// # std::hint::black_box(&MEMORY_MAP_REQUEST);
+    /// // Find the request in this very program
+    /// let image = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    /// let scan = Scan::new(&image).unwrap();
+    ///
+    /// let request = scan
+    ///     .requests()
+    ///     .find(|r| r.location.section == ".requests")
+    ///     .unwrap();
+    /// assert_eq!(request.name, Some("MemoryMapRequest"));
+    /// assert_eq!(request.revision, 2);
+    /// ```
+    pub fn new(image: &'a [u8]) -> Result<Self, Error> {
+        let mut scan = Self {
+            elf: Elf::new(image)?,
+            start_marker: None,
+            end_marker: None,
+        };
+
+        let (mut start_marker, mut end_marker) = (None, None);
+        for section in scan.sections() {
+            for offset in Self::aligned_offsets(&section) {
+                let location = scan.location(&section, offset);
+                if read_words(section.data, offset) == Some(RequestsStartMarker::ID) {
+                    start_marker = Some(location);
+                } else if read_words(section.data, offset) == Some(RequestsEndMarker::ID) {
+                    end_marker = Some(location);
+                }
+            }
+        }
+        scan.start_marker = start_marker;
+        scan.end_marker = end_marker;
+
+        Ok(scan)
+    }
+
+    /// Where the [`RequestsStartMarker`] was found, if present.
+    pub fn start_marker(&self) -> Option<Location<'a>> {
+        self.start_marker
+    }
+
+    /// Where the [`RequestsEndMarker`] was found, if present.
+    pub fn end_marker(&self) -> Option<Location<'a>> {
+        self.end_marker
+    }
+
+    /// The [`BaseRevision`] tags found in the executable. There should be
+    /// exactly one.
+    pub fn base_revisions(&self) -> impl Iterator<Item = FoundBaseRevision<'a>> + 'a {
+        let this = *self;
+        this.find(move |section, offset| {
+            let [magic_1, magic_2, revision] = read_words::<3>(section.data, offset)?;
+            if [magic_1, magic_2] != [BaseRevision::MAGIC_1, BaseRevision::MAGIC_2] {
+                return None;
+            }
+            let location = this.location(section, offset);
+            Some(FoundBaseRevision {
+                revision,
+                location,
+                in_markers: this.in_markers(&location),
+            })
+        })
+    }
+
+    /// The requests found in the executable.
+    pub fn requests(&self) -> impl Iterator<Item = FoundRequest<'a>> + 'a {
+        let this = *self;
+        this.find(move |section, offset| {
+            let [a, b, c, d, revision] = read_words::<5>(section.data, offset)?;
+            if [a, b] != COMMON_MAGIC {
+                return None;
+            }
+            let id = [a, b, c, d];
+            let location = this.location(section, offset);
+            Some(FoundRequest {
+                name: KNOWN_REQUESTS
+                    .iter()
+                    .find(|(_, known)| *known == id)
+                    .map(|(name, _)| *name),
+                id,
+                revision,
+                location,
+                in_markers: this.in_markers(&location),
+            })
+        })
+    }
+
+    fn find<T>(
+        self,
+        f: impl Fn(&Section<'a>, usize) -> Option<T> + 'a,
+    ) -> impl Iterator<Item = T> + 'a {
+        self.sections()
+            .flat_map(move |section| {
+                Self::aligned_offsets(&section).map(move |offset| (section, offset))
+            })
+            .filter_map(move |(section, offset)| f(&section, offset))
+    }
+
+    fn in_markers(&self, location: &Location<'_>) -> Option<bool> {
+        let start = self.start_marker?.address;
+        let end = self.end_marker?.address;
+        Some(start < location.address && location.address < end)
+    }
+
+    fn location(&self, section: &Section<'a>, offset: usize) -> Location<'a> {
+        Location {
+            section: section.name,
+            section_offset: offset as u64,
+            file_offset: section.offset + offset as u64,
+            address: section.addr + offset as u64,
+        }
+    }
+
+    /// Offsets into the section at which an 8-byte aligned item could start.
+    fn aligned_offsets(section: &Section<'a>) -> impl Iterator<Item = usize> {
+        let first = (section.addr.wrapping_neg() % 8) as usize;
+        (first..section.data.len()).step_by(8)
+    }
+
+    /// The allocated, non-empty sections of the image.
+    fn sections(self) -> impl Iterator<Item = Section<'a>> + 'a {
+        self.elf.sections().filter(|section| {
+            section.ty != SHT_NOBITS && section.flags & SHF_ALLOC != 0 && section.size != 0
+        })
+    }
+}
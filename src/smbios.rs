@@ -0,0 +1,530 @@
+//! Parsing of the SMBIOS structure table, starting from the [SMBIOS
+//! response](crate::response::SmbiosResponse).
+//!
+//! Both the 32-bit (`_SM_`) and 64-bit (`_SM3_`) entry points are validated
+//! before the structure table is read in place through the higher-half direct
+//! map. Typed views are provided for the BIOS information, system
+//! information, baseboard, processor and memory device structures; other
+//! structures can be read from [`Structure::data`] and [`Structure::string`].
+//!
+//! # Examples
+//! ```rust
+//! use limine::{addr::PhysAddr, smbios::Smbios};
+//! # // Lay out a 64-bit entry point and a structure table in fake physical memory
+//! # // at 0x20000.
+//! # let mut words = vec![0u64; 0x20];
+//! # let memory = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), 0x100) };
+//! # let table: &[u8] = &[
+//! #     0, 0x18, 0, 0, 1, 2, 0, 0xf0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+//! #     b'A', b'c', b'm', b'e', 0, b'1', b'.', b'0', 0, 0, // BIOS information
+//! #     1, 0x08, 1, 0, 1, 2, 0, 0, b'A', b'c', b'm', b'e', 0, b'R', b'o', b'a', b'd', b'r', b'u', b'n', b'n', b'e', b'r', 0, 0,
+//! #     127, 4, 2, 0, 0, 0, // end of table
+//! # ];
+//! # memory[..5].copy_from_slice(b"_SM3_");
+//! # memory[6] = 24;
+//! # memory[7] = 3;
+//! # memory[8] = 6;
+//! # memory[10] = 1;
+//! # memory[12..16].copy_from_slice(&(table.len() as u32).to_le_bytes());
+//! # memory[16..24].copy_from_slice(&0x20020u64.to_le_bytes());
+//! # memory[5] = 0u8.wrapping_sub(memory[..24].iter().fold(0u8, |a, &b| a.wrapping_add(b)));
+//! # memory[0x20..0x20 + table.len()].copy_from_slice(table);
+//! # let hhdm_offset = (memory.as_ptr() as u64).wrapping_sub(0x20000);
+//! # let entry_64 = PhysAddr::new(0x20000);
+//!
+//! // SAFETY: the entry point and table are mapped in the higher-half direct map
+//! let smbios = unsafe { Smbios::new_64(entry_64, hhdm_offset) }.unwrap();
+//! assert_eq!(smbios.version(), (3, 6));
+//!
+//! let bios = smbios.bios_info().unwrap();
+//! assert_eq!(bios.vendor(), Some("Acme"));
+//! assert_eq!(bios.version(), Some("1.0"));
+//!
+//! let system = smbios.system_info().unwrap();
+//! assert_eq!(system.product_name(), Some("Roadrunner"));
+//! assert_eq!(smbios.structures().count(), 2);
+//! ```
+
+use crate::{
+    addr::PhysAddr,
+    file::Uuid,
+    phys::{checksum, map, read, read_u16, read_u32, read_u64, response_address},
+    response::{HhdmResponse, SmbiosResponse},
+    Error,
+};
+
+/// The SMBIOS structure table, reached through the higher-half direct map.
+#[derive(Clone, Copy)]
+pub struct Smbios<'a> {
+    version: (u8, u8),
+    table: &'a [u8],
+    count: Option<u16>,
+}
+impl<'a> Smbios<'a> {
+    /// Validate the 32-bit (`_SM_`) entry point at `entry`, including its
+    /// intermediate `_DMI_` anchor.
+    ///
+    /// # Safety
+    /// `hhdm_offset` must be the offset of a direct map covering the entry
+    /// point and the structure table, such as the one returned by
+    /// [`HhdmResponse::offset`], and the table must not be modified during
+    /// `'a`.
+    pub unsafe fn new_32(entry: PhysAddr, hhdm_offset: u64) -> Result<Self, Error> {
+        let header = map(entry, hhdm_offset, 6)?;
+        if header[..4] != *b"_SM_" {
+            return Err(Error::InvalidSignature);
+        }
+        if header[5] < 0x1f {
+            return Err(Error::InvalidLength);
+        }
+        let bytes = map(entry, hhdm_offset, header[5] as usize)?;
+        if bytes[0x10..0x15] != *b"_DMI_" {
+            return Err(Error::InvalidSignature);
+        }
+        if !checksum(bytes) || !checksum(&bytes[0x10..0x1f]) {
+            return Err(Error::InvalidChecksum);
+        }
+        let length = read_u16(bytes, 0x16).unwrap();
+        let address = read_u32(bytes, 0x18).unwrap();
+        Ok(Self {
+            version: (bytes[6], bytes[7]),
+            table: map(PhysAddr::new(address.into()), hhdm_offset, length as usize)?,
+            count: read_u16(bytes, 0x1c),
+        })
+    }
+
+    /// Validate the 64-bit (`_SM3_`) entry point at `entry`.
+    ///
+    /// # Safety
+    /// See [`new_32`](Self::new_32).
+    pub unsafe fn new_64(entry: PhysAddr, hhdm_offset: u64) -> Result<Self, Error> {
+        let header = map(entry, hhdm_offset, 7)?;
+        if header[..5] != *b"_SM3_" {
+            return Err(Error::InvalidSignature);
+        }
+        if header[6] < 0x18 {
+            return Err(Error::InvalidLength);
+        }
+        let bytes = map(entry, hhdm_offset, header[6] as usize)?;
+        if !checksum(bytes) {
+            return Err(Error::InvalidChecksum);
+        }
+        let length = read_u32(bytes, 0x0c).unwrap();
+        let address = read_u64(bytes, 0x10).unwrap();
+        Ok(Self {
+            version: (bytes[7], bytes[8]),
+            table: map(PhysAddr::new(address), hhdm_offset, length as usize)?,
+            count: None,
+        })
+    }
+
+    /// Validate the entry point given by the bootloader, preferring the 64-bit
+    /// entry point if both exist. This accepts both the physical addresses
+    /// returned from base revision 3, and the higher-half direct map addresses
+    /// returned by earlier base revisions.
+    ///
+    /// # Safety
+    /// See [`new_32`](Self::new_32).
+    pub unsafe fn from_response(
+        smbios: &SmbiosResponse,
+        hhdm: &HhdmResponse,
+    ) -> Result<Self, Error> {
+        let phys = |address: usize| response_address(address as u64, hhdm);
+        match (smbios.entry_64(), smbios.entry_32()) {
+            (Some(entry), _) => Self::new_64(phys(entry.get()), hhdm.offset()),
+            (None, Some(entry)) => Self::new_32(phys(entry.get()), hhdm.offset()),
+            (None, None) => Err(Error::NullPointer),
+        }
+    }
+
+    /// The SMBIOS version, as `(major, minor)`.
+    pub fn version(&self) -> (u8, u8) {
+        self.version
+    }
+
+    /// The bytes of the structure table.
+    pub fn table(&self) -> &'a [u8] {
+        self.table
+    }
+
+    /// Returns every structure in the table, up to the end-of-table
+    /// structure.
+    pub fn structures(&self) -> Structures<'a> {
+        Structures {
+            rest: self.table,
+            remaining: self.count,
+        }
+    }
+
+    /// Find the first structure with the given handle.
+    pub fn find_handle(&self, handle: u16) -> Option<Structure<'a>> {
+        self.structures().find(|s| s.handle() == handle)
+    }
+
+    /// Find the BIOS information structure.
+    pub fn bios_info(&self) -> Option<BiosInfo<'a>> {
+        self.structures().find_map(BiosInfo::new)
+    }
+
+    /// Find the system information structure.
+    pub fn system_info(&self) -> Option<SystemInfo<'a>> {
+        self.structures().find_map(SystemInfo::new)
+    }
+
+    /// Returns the baseboard information structures.
+    pub fn baseboards(&self) -> impl Iterator<Item = Baseboard<'a>> + 'a {
+        self.structures().filter_map(Baseboard::new)
+    }
+
+    /// Returns the processor information structures, one per socket.
+    pub fn processors(&self) -> impl Iterator<Item = Processor<'a>> + 'a {
+        self.structures().filter_map(Processor::new)
+    }
+
+    /// Returns the memory device structures, one per memory slot.
+    pub fn memory_devices(&self) -> impl Iterator<Item = MemoryDevice<'a>> + 'a {
+        self.structures().filter_map(MemoryDevice::new)
+    }
+}
+
+/// An iterator over the structures of an SMBIOS table, as returned by
+/// [`Smbios::structures`].
+#[derive(Clone)]
+pub struct Structures<'a> {
+    rest: &'a [u8],
+    remaining: Option<u16>,
+}
+impl<'a> Iterator for Structures<'a> {
+    type Item = Structure<'a>;
+
+    fn next(&mut self) -> Option<Structure<'a>> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+        let len = *self.rest.get(1)? as usize;
+        let end = self
+            .rest
+            .get(len..)
+            .and_then(|tail| tail.windows(2).position(|w| w == [0, 0]))
+            .filter(|_| len >= 4);
+        let Some(end) = end else {
+            self.rest = &[];
+            return None;
+        };
+        let (data, strings) = self.rest[..len + end].split_at(len);
+        self.rest = &self.rest[len + end + 2..];
+        self.remaining = self.remaining.map(|n| n - 1);
+        (data[0] != Structure::END_OF_TABLE).then_some(Structure { data, strings })
+    }
+}
+
+/// An SMBIOS structure: a formatted area followed by a set of strings.
+#[derive(Clone, Copy)]
+pub struct Structure<'a> {
+    data: &'a [u8],
+    strings: &'a [u8],
+}
+impl<'a> Structure<'a> {
+    /// The type of the structure marking the end of the table.
+    pub const END_OF_TABLE: u8 = 127;
+
+    /// The type of the structure.
+    pub fn ty(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// The handle of the structure, used by other structures to refer to it.
+    pub fn handle(&self) -> u16 {
+        read_u16(self.data, 2).unwrap()
+    }
+
+    /// The formatted area of the structure, including its 4-byte header.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the string with the given 1-based index, as stored in the
+    /// formatted area. Index 0, empty strings and strings which are not UTF-8
+    /// return `None`.
+    pub fn string(&self, index: u8) -> Option<&'a str> {
+        let string = self
+            .strings
+            .split(|&b| b == 0)
+            .nth(usize::from(index).checked_sub(1)?)?;
+        core::str::from_utf8(string)
+            .ok()
+            .map(|s| s.trim_end())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    fn string_at(&self, offset: usize) -> Option<&'a str> {
+        self.string(self.byte(offset)?)
+    }
+}
+
+macro_rules! structure_view {
+    ($(#[$meta:meta])* $name:ident = $ty:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy)]
+        pub struct $name<'a>(Structure<'a>);
+        impl<'a> $name<'a> {
+            /// The structure type of this view.
+            pub const TYPE: u8 = $ty;
+
+            /// View a structure as this type, if it has the right type.
+            pub fn new(structure: Structure<'a>) -> Option<Self> {
+                (structure.ty() == Self::TYPE).then_some(Self(structure))
+            }
+
+            /// The underlying structure.
+            pub fn structure(&self) -> Structure<'a> {
+                self.0
+            }
+        }
+    };
+}
+
+structure_view! {
+    /// The BIOS information structure (type 0).
+    BiosInfo = 0
+}
+impl<'a> BiosInfo<'a> {
+    /// The BIOS vendor.
+    pub fn vendor(&self) -> Option<&'a str> {
+        self.0.string_at(0x04)
+    }
+
+    /// The BIOS version.
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.string_at(0x05)
+    }
+
+    /// The BIOS release date, usually as `mm/dd/yyyy`.
+    pub fn release_date(&self) -> Option<&'a str> {
+        self.0.string_at(0x08)
+    }
+
+    /// The BIOS characteristics bit field.
+    pub fn characteristics(&self) -> Option<u64> {
+        read_u64(self.0.data, 0x0a)
+    }
+
+    /// The system BIOS release, as `(major, minor)`. Returns `None` if it is
+    /// not given.
+    pub fn release(&self) -> Option<(u8, u8)> {
+        Some((self.0.byte(0x14)?, self.0.byte(0x15)?)).filter(|&r| r != (0xff, 0xff))
+    }
+}
+
+structure_view! {
+    /// The system information structure (type 1).
+    SystemInfo = 1
+}
+impl<'a> SystemInfo<'a> {
+    /// The system manufacturer.
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.string_at(0x04)
+    }
+
+    /// The product name.
+    pub fn product_name(&self) -> Option<&'a str> {
+        self.0.string_at(0x05)
+    }
+
+    /// The product version.
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.string_at(0x06)
+    }
+
+    /// The serial number.
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.string_at(0x07)
+    }
+
+    /// The system UUID. Returns `None` if it is not present, which firmware
+    /// signals with all bits clear or all bits set.
+    pub fn uuid(&self) -> Option<Uuid> {
+        let bytes: [u8; 16] = read(self.0.data, 0x08)?;
+        if bytes == [0; 16] || bytes == [0xff; 16] {
+            return None;
+        }
+        Some(Uuid {
+            a: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            b: u16::from_le_bytes([bytes[4], bytes[5]]),
+            c: u16::from_le_bytes([bytes[6], bytes[7]]),
+            d: bytes[8..].try_into().unwrap(),
+        })
+    }
+
+    /// The SKU number.
+    pub fn sku_number(&self) -> Option<&'a str> {
+        self.0.string_at(0x19)
+    }
+
+    /// The family of the product.
+    pub fn family(&self) -> Option<&'a str> {
+        self.0.string_at(0x1a)
+    }
+}
+
+structure_view! {
+    /// The baseboard (or module) information structure (type 2).
+    Baseboard = 2
+}
+impl<'a> Baseboard<'a> {
+    /// The baseboard manufacturer.
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.string_at(0x04)
+    }
+
+    /// The product name.
+    pub fn product(&self) -> Option<&'a str> {
+        self.0.string_at(0x05)
+    }
+
+    /// The product version.
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.string_at(0x06)
+    }
+
+    /// The serial number.
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.string_at(0x07)
+    }
+
+    /// The asset tag.
+    pub fn asset_tag(&self) -> Option<&'a str> {
+        self.0.string_at(0x08)
+    }
+}
+
+structure_view! {
+    /// The processor information structure (type 4).
+    Processor = 4
+}
+impl<'a> Processor<'a> {
+    /// The designation of the socket, such as `CPU0`.
+    pub fn socket(&self) -> Option<&'a str> {
+        self.0.string_at(0x04)
+    }
+
+    /// The processor family, as defined by the SMBIOS specification.
+    pub fn family(&self) -> Option<u16> {
+        match self.0.byte(0x06)? {
+            0xfe => read_u16(self.0.data, 0x28),
+            family => Some(family.into()),
+        }
+    }
+
+    /// The processor manufacturer.
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.string_at(0x07)
+    }
+
+    /// The raw processor ID, which is the CPUID signature and feature flags
+    /// on x86.
+    pub fn id(&self) -> Option<u64> {
+        read_u64(self.0.data, 0x08)
+    }
+
+    /// The processor version, usually its brand string.
+    pub fn version(&self) -> Option<&'a str> {
+        self.0.string_at(0x10)
+    }
+
+    /// The maximum speed supported by the socket, in MHz.
+    pub fn max_speed_mhz(&self) -> Option<u16> {
+        read_u16(self.0.data, 0x14).filter(|&speed| speed != 0)
+    }
+
+    /// The speed at boot, in MHz.
+    pub fn current_speed_mhz(&self) -> Option<u16> {
+        read_u16(self.0.data, 0x16).filter(|&speed| speed != 0)
+    }
+
+    /// Returns whether the socket is populated.
+    pub fn is_populated(&self) -> bool {
+        self.0.byte(0x18).is_some_and(|status| status & 0x40 != 0)
+    }
+
+    /// The number of cores in the processor.
+    pub fn core_count(&self) -> Option<u16> {
+        match self.0.byte(0x23)? {
+            0 => None,
+            0xff => read_u16(self.0.data, 0x2a),
+            count => Some(count.into()),
+        }
+    }
+
+    /// The number of threads in the processor.
+    pub fn thread_count(&self) -> Option<u16> {
+        match self.0.byte(0x25)? {
+            0 => None,
+            0xff => read_u16(self.0.data, 0x2e),
+            count => Some(count.into()),
+        }
+    }
+}
+
+structure_view! {
+    /// The memory device structure (type 17), describing one memory slot.
+    MemoryDevice = 17
+}
+impl<'a> MemoryDevice<'a> {
+    /// The size of the installed memory, in bytes. Returns `Some(0)` for an
+    /// empty slot, and `None` if the size is unknown.
+    pub fn size(&self) -> Option<u64> {
+        const KIB: u64 = 1 << 10;
+        const MIB: u64 = 1 << 20;
+        match read_u16(self.0.data, 0x0c)? {
+            0xffff => None,
+            0x7fff => Some(u64::from(read_u32(self.0.data, 0x1c)? & 0x7fff_ffff) * MIB),
+            size if size & 0x8000 != 0 => Some(u64::from(size & 0x7fff) * KIB),
+            size => Some(u64::from(size) * MIB),
+        }
+    }
+
+    /// The form factor, such as 0x09 for DIMM or 0x0d for SODIMM.
+    pub fn form_factor(&self) -> Option<u8> {
+        self.0.byte(0x0e)
+    }
+
+    /// The label of the slot, such as `DIMM 0`.
+    pub fn device_locator(&self) -> Option<&'a str> {
+        self.0.string_at(0x10)
+    }
+
+    /// The label of the bank, such as `BANK 0`.
+    pub fn bank_locator(&self) -> Option<&'a str> {
+        self.0.string_at(0x11)
+    }
+
+    /// The memory type, such as 0x1a for DDR4 or 0x22 for DDR5.
+    pub fn memory_type(&self) -> Option<u8> {
+        self.0.byte(0x12)
+    }
+
+    /// The maximum speed of the device, in MT/s.
+    pub fn speed_mts(&self) -> Option<u16> {
+        read_u16(self.0.data, 0x15).filter(|&speed| speed != 0)
+    }
+
+    /// The manufacturer of the device.
+    pub fn manufacturer(&self) -> Option<&'a str> {
+        self.0.string_at(0x17)
+    }
+
+    /// The serial number of the device.
+    pub fn serial_number(&self) -> Option<&'a str> {
+        self.0.string_at(0x18)
+    }
+
+    /// The part number of the device.
+    pub fn part_number(&self) -> Option<&'a str> {
+        self.0.string_at(0x1a)
+    }
+}